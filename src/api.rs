@@ -0,0 +1,266 @@
+use crate::{
+    auth::Scope, i18n::tr, listen::PeerAddr, model::Interval, ratelimit::RateLimit,
+    validate::ValidationErrors,
+};
+use serde::de::IgnoredAny;
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+use tracing::error;
+use warp::{
+    http::{HeaderMap, StatusCode},
+    reject::{
+        InvalidHeader, LengthRequired, MethodNotAllowed, MissingHeader, PayloadTooLarge,
+        UnsupportedMediaType,
+    },
+    reply::{Reply, Response},
+    Filter, Rejection,
+};
+
+pub(crate) const MAX_BODY_BYTES: u64 = 10 * 1024;
+
+pub(crate) const OPENAPI: &str = include_str!("openapi.json");
+
+// Client address for plain and TLS connections alike.
+pub(crate) fn remote() -> impl Filter<Extract = (Option<SocketAddr>,), Error = Infallible> + Copy {
+    warp::addr::remote()
+        .and(warp::ext::optional::<PeerAddr>())
+        .map(|addr: Option<SocketAddr>, peer: Option<PeerAddr>| {
+            addr.or(peer.map(|PeerAddr(addr)| addr))
+        })
+}
+
+// The body is read as bytes so that signatures cover exactly what was sent,
+// but its content type is held to what `warp::body::json` accepts; without a
+// `Content-Type`, JSON is assumed.
+pub(crate) fn json_content_type() -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    let json = warp::header::optional::<String>("content-type")
+        .and_then(|content_type: Option<String>| async move {
+            let is_json = content_type.as_deref().is_none_or(|t| {
+                t.split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .eq_ignore_ascii_case("application/json")
+            });
+            if is_json {
+                Ok(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+        .untuple_one();
+    // Only reached for other content types, which warp rejects with
+    // `UnsupportedMediaType` before reading the body.
+    json.or(warp::body::json::<IgnoredAny>().map(|_| ()))
+        .map(|_| ())
+        .untuple_one()
+}
+
+// The address of the client. Behind a trusted reverse proxy that is the last
+// `X-Forwarded-For` hop not added by a trusted proxy; the header is ignored
+// on connections from anywhere else, since clients can set it freely.
+pub(crate) fn client(
+    trusted_proxies: Arc<[IpAddr]>,
+) -> impl Filter<Extract = (Option<IpAddr>,), Error = Infallible> + Clone {
+    remote().and(warp::header::headers_cloned()).map(
+        move |remote: Option<SocketAddr>, headers: HeaderMap| {
+            let mut client = remote?.ip();
+            let hops = headers
+                .get_all("x-forwarded-for")
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .collect::<Vec<_>>();
+            for hop in hops.into_iter().rev() {
+                if !trusted_proxies.contains(&client) {
+                    break;
+                }
+                match hop.trim().parse() {
+                    Ok(address) => client = address,
+                    Err(_) => break,
+                }
+            }
+            Some(client)
+        },
+    )
+}
+
+// Every failed request is answered with
+// `{"status": "error", "code": ..., "message": ..., "errors": [...]}`, where
+// `code` is stable and `message` is localized. `errors` is only present for
+// validation failures.
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+    errors: Option<ValidationErrors>,
+    rate_limit: Option<RateLimit>,
+}
+
+impl ApiError {
+    pub(crate) fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            code,
+            message: message.into(),
+            errors: None,
+            rate_limit: None,
+        }
+    }
+
+    pub(crate) fn validation(errors: ValidationErrors) -> Self {
+        let code = if errors.0.iter().any(|e| e.code == "malformed_json") {
+            "malformed_json"
+        } else {
+            "validation_failed"
+        };
+        ApiError {
+            status: StatusCode::BAD_REQUEST,
+            code,
+            message: errors.to_string(),
+            errors: Some(errors),
+            rate_limit: None,
+        }
+    }
+
+    pub(crate) fn rate_limited(rate_limit: RateLimit) -> Self {
+        ApiError {
+            rate_limit: Some(rate_limit),
+            ..ApiError::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                "rate_limited",
+                tr!(ApiRateLimited),
+            )
+        }
+    }
+
+    pub(crate) fn is_rate_limited(&self) -> bool {
+        self.status == StatusCode::TOO_MANY_REQUESTS
+    }
+
+    pub(crate) fn unauthorized() -> Self {
+        ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "unauthorized",
+            tr!(ApiUnauthorized),
+        )
+    }
+
+    pub(crate) fn token_expired() -> Self {
+        ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "token_expired",
+            tr!(ApiTokenExpired),
+        )
+    }
+
+    pub(crate) fn stale_timestamp(window: Interval) -> Self {
+        ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "stale_timestamp",
+            tr!(ApiSignatureStale, window = window),
+        )
+    }
+
+    pub(crate) fn replayed() -> Self {
+        ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "replayed",
+            tr!(ApiSignatureReplayed),
+        )
+    }
+
+    pub(crate) fn forbidden(scope: Scope) -> Self {
+        ApiError::new(
+            StatusCode::FORBIDDEN,
+            "forbidden",
+            tr!(ApiForbidden, scope = scope),
+        )
+    }
+
+    pub(crate) fn invalid_query(error: impl std::fmt::Display) -> Self {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_query",
+            tr!(ApiInvalidQuery, error = error),
+        )
+    }
+}
+
+impl Reply for ApiError {
+    fn into_response(self) -> Response {
+        let mut body = serde_json::json!({
+            "status": "error",
+            "code": self.code,
+            "message": self.message,
+        });
+        if let Some(errors) = self.errors {
+            body["errors"] = serde_json::json!(errors);
+        }
+        let mut response =
+            warp::reply::with_status(warp::reply::json(&body), self.status).into_response();
+        if let Some(rate_limit) = self.rate_limit {
+            rate_limit.apply(&mut response);
+        }
+        response
+    }
+}
+
+// Turns warp's built-in rejections (unknown path, wrong method, oversized
+// body, ...) into the same schema as handler errors.
+pub(crate) async fn handle_rejection(rejection: Rejection) -> Result<Response, Infallible> {
+    let error = if rejection.is_not_found() {
+        ApiError::new(StatusCode::NOT_FOUND, "not_found", tr!(ApiNotFound))
+    } else if rejection.find::<MethodNotAllowed>().is_some() {
+        ApiError::new(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "method_not_allowed",
+            tr!(ApiMethodNotAllowed),
+        )
+    } else if rejection.find::<PayloadTooLarge>().is_some() {
+        ApiError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "payload_too_large",
+            tr!(ApiPayloadTooLarge, max = MAX_BODY_BYTES),
+        )
+    } else if rejection.find::<LengthRequired>().is_some() {
+        ApiError::new(
+            StatusCode::LENGTH_REQUIRED,
+            "length_required",
+            tr!(ApiLengthRequired),
+        )
+    } else if rejection.find::<UnsupportedMediaType>().is_some() {
+        ApiError::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "unsupported_media_type",
+            tr!(ApiUnsupportedMediaType),
+        )
+    } else if let Some(e) = rejection.find::<InvalidHeader>() {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_header",
+            tr!(ApiInvalidHeader, header = e.name()),
+        )
+    } else if let Some(e) = rejection.find::<MissingHeader>() {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_header",
+            tr!(ApiInvalidHeader, header = e.name()),
+        )
+    } else {
+        error!(
+            "{}",
+            tr!(ApiUnhandledRejection, error = format!("{:?}", rejection))
+        );
+        ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal",
+            tr!(ApiInternalError),
+        )
+    };
+    Ok(error.into_response())
+}
@@ -0,0 +1,296 @@
+use crate::{config::AppConfig, model::Interval};
+use chrono::{DateTime, FixedOffset, Local};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fmt,
+    fmt::Write,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+pub(crate) const HASH_PREFIX: &str = "sha256:";
+
+pub(crate) const SIGNATURE_HEADER: &str = "x-pushel-signature";
+pub(crate) const TIMESTAMP_HEADER: &str = "x-pushel-timestamp";
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+// The plain `api_token` from older configs; it keeps full access.
+const LEGACY_TOKEN_NAME: &str = "default";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+pub(crate) enum Scope {
+    #[serde(rename = "notify")]
+    #[value(name = "notify")]
+    Notify,
+    #[serde(rename = "reminders:write")]
+    #[value(name = "reminders:write")]
+    RemindersWrite,
+    // History, events, metrics and health details; implies every other scope.
+    #[serde(rename = "admin")]
+    #[value(name = "admin")]
+    Admin,
+}
+
+impl Scope {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Scope::Notify => "notify",
+            Scope::RemindersWrite => "reminders:write",
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// The caller behind a valid token.
+#[derive(Debug, Clone)]
+pub(crate) struct Identity {
+    pub(crate) name: String,
+    scopes: Vec<Scope>,
+    pub(crate) rate_limit_rpm: Option<u32>,
+    // Webhook signature to enter into the replay cache once the request is
+    // accepted, see `Tokens::record_signature`.
+    signature: Option<String>,
+}
+
+impl Identity {
+    pub(crate) fn allows(&self, scope: Scope) -> bool {
+        self.scopes.contains(&Scope::Admin) || self.scopes.contains(&scope)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum AuthError {
+    Invalid,
+    Expired(String),
+    // The webhook timestamp is outside the replay window.
+    Stale(String),
+    Replayed(String),
+}
+
+struct Token {
+    name: String,
+    hash: String,
+    scopes: Vec<Scope>,
+    expires_at: Option<DateTime<FixedOffset>>,
+    rate_limit_rpm: Option<u32>,
+}
+
+// HMAC needs the secret itself, so unlike tokens it can't be stored hashed.
+struct Sender {
+    name: String,
+    secret: String,
+}
+
+// Configured API tokens and webhook senders. Only token hashes are kept, so a
+// leaked config or memory dump doesn't hand out working bearer secrets.
+pub(crate) struct Tokens {
+    inner: Arc<Vec<Token>>,
+    senders: Arc<Vec<Sender>>,
+    replay_window: Interval,
+    // Signatures seen within the replay window.
+    seen: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl Tokens {
+    pub(crate) fn new(config: &AppConfig) -> Self {
+        let legacy = config.api_token.as_deref().map(|secret| Token {
+            name: LEGACY_TOKEN_NAME.to_string(),
+            hash: hash(secret),
+            scopes: vec![Scope::Admin],
+            expires_at: None,
+            rate_limit_rpm: None,
+        });
+        let configured = config.api_tokens.iter().map(|token| Token {
+            name: token.name.clone(),
+            hash: token.hash.to_ascii_lowercase(),
+            scopes: token.scopes.clone(),
+            expires_at: token.expires_at,
+            rate_limit_rpm: token.rate_limit_rpm,
+        });
+        let senders = config
+            .webhook_senders
+            .iter()
+            .map(|sender| Sender {
+                name: sender.name.clone(),
+                secret: sender.secret.clone(),
+            })
+            .collect();
+        Tokens {
+            inner: Arc::new(legacy.into_iter().chain(configured).collect()),
+            senders: Arc::new(senders),
+            replay_window: config.webhook_replay_window,
+            seen: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.inner.is_empty() && self.senders.is_empty()
+    }
+
+    // `Ok(None)` means no tokens are configured and the API is open.
+    pub(crate) fn authenticate(
+        &self,
+        auth_header: Option<&str>,
+    ) -> Result<Option<Identity>, AuthError> {
+        self.authenticate_secret(auth_header.and_then(|h| h.strip_prefix("Bearer ")))
+    }
+
+    // For clients that carry the token elsewhere than in a bearer header.
+    pub(crate) fn authenticate_secret(
+        &self,
+        secret: Option<&str>,
+    ) -> Result<Option<Identity>, AuthError> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let provided = hash(secret.ok_or(AuthError::Invalid)?);
+        let token = self
+            .inner
+            .iter()
+            .find(|token| constant_time_eq(&provided, &token.hash))
+            .ok_or(AuthError::Invalid)?;
+        if token.expires_at.is_some_and(|at| at <= Local::now()) {
+            return Err(AuthError::Expired(token.name.clone()));
+        }
+        Ok(Some(Identity {
+            name: token.name.clone(),
+            scopes: token.scopes.clone(),
+            rate_limit_rpm: token.rate_limit_rpm,
+            signature: None,
+        }))
+    }
+
+    // Webhook senders sign `<timestamp>.<body>` with HMAC-SHA256 and may only
+    // send notifications.
+    pub(crate) fn verify_signature(
+        &self,
+        signature: &str,
+        timestamp: Option<&str>,
+        body: &[u8],
+    ) -> Result<Identity, AuthError> {
+        let provided = signature
+            .strip_prefix(SIGNATURE_PREFIX)
+            .ok_or(AuthError::Invalid)?
+            .to_ascii_lowercase();
+        let timestamp = timestamp.ok_or(AuthError::Invalid)?;
+        let sender = self
+            .senders
+            .iter()
+            .find(|sender| constant_time_eq(&provided, &sign(&sender.secret, timestamp, body)))
+            .ok_or(AuthError::Invalid)?;
+
+        let sent_at = timestamp.parse::<u64>().map_err(|_| AuthError::Invalid)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let window = self.replay_window.duration();
+        if now.abs_diff(sent_at) > window.as_secs() {
+            return Err(AuthError::Stale(sender.name.clone()));
+        }
+
+        if self.seen().contains_key(&provided) {
+            return Err(AuthError::Replayed(sender.name.clone()));
+        }
+        Ok(Identity {
+            name: sender.name.clone(),
+            scopes: vec![Scope::Notify],
+            rate_limit_rpm: None,
+            signature: Some(provided),
+        })
+    }
+
+    // Only requests that were accepted use up their signature, so that one
+    // rejected by the rate limit or validation can be retried.
+    pub(crate) fn record_signature(&self, identity: &Identity) -> Result<(), AuthError> {
+        let Some(signature) = &identity.signature else {
+            return Ok(());
+        };
+        if self
+            .seen()
+            .insert(signature.clone(), Instant::now())
+            .is_some()
+        {
+            return Err(AuthError::Replayed(identity.name.clone()));
+        }
+        Ok(())
+    }
+
+    fn seen(&self) -> MutexGuard<'_, HashMap<String, Instant>> {
+        let mut seen = self.seen.lock().expect("replay cache mutex poisoned");
+        let now = Instant::now();
+        let window = self.replay_window.duration();
+        // Twice the window, since timestamps may also lie in the future.
+        seen.retain(|_, at| now.duration_since(*at) <= 2 * window);
+        seen
+    }
+
+    pub(crate) fn replay_window(&self) -> Interval {
+        self.replay_window
+    }
+}
+
+impl Clone for Tokens {
+    fn clone(&self) -> Self {
+        Tokens {
+            inner: Arc::clone(&self.inner),
+            senders: Arc::clone(&self.senders),
+            replay_window: self.replay_window,
+            seen: Arc::clone(&self.seen),
+        }
+    }
+}
+
+pub(crate) fn hash(secret: &str) -> String {
+    let digest = Sha256::digest(secret.as_bytes());
+    let mut out = String::from(HASH_PREFIX);
+    for byte in digest {
+        let _ = write!(out, "{byte:02x}");
+    }
+    out
+}
+
+fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    let mut out = String::new();
+    for byte in mac.finalize().into_bytes() {
+        let _ = write!(out, "{byte:02x}");
+    }
+    out
+}
+
+pub(crate) fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    let mut out = String::from("pshl_");
+    for byte in bytes {
+        let _ = write!(out, "{byte:02x}");
+    }
+    out
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
@@ -0,0 +1,125 @@
+use crate::{
+    config::AppConfig, history::Page, i18n::tr, listen::ListenAddress, send_notification, template,
+    validate::Validate, AdhocNotification,
+};
+use std::{
+    fs,
+    net::{IpAddr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+enum DaemonError {
+    Unreachable(String),
+    Rejected(String),
+}
+
+// Prefer the daemon so its delivery rules apply; fall back to notify-send only
+// when it can't be reached, not when it refused the notification.
+pub(crate) async fn deliver(
+    notification: AdhocNotification,
+    config: Option<&AppConfig>,
+) -> Result<(), String> {
+    notification.validate()?;
+    if let Some(config) = config.filter(|c| c.webserver_enabled) {
+        match post_to_daemon(&notification, config).await {
+            Ok(()) => return Ok(()),
+            Err(DaemonError::Rejected(e)) => return Err(tr!(DaemonRejected, error = e)),
+            Err(DaemonError::Unreachable(e)) => {
+                eprintln!("{}", tr!(DaemonUnreachable, error = e))
+            }
+        }
+    }
+    let defaults = config.map(|c| c.defaults.clone()).unwrap_or_default();
+    send_notification(&notification.render(&defaults, template::builtin_vars(0))).map(|_| ())
+}
+
+// Most recent entries of the daemon's notification history.
+pub(crate) async fn fetch_history(config: &AppConfig) -> Result<Page, String> {
+    if !config.webserver_enabled {
+        return Err(tr!(TuiHistoryNeedsWebserver).to_string());
+    }
+    let url = daemon_url(config, "history?limit=500")?;
+    let client = http_client(config)?;
+    let mut request = client.get(&url);
+    if let Some(token) = &config.api_token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(error_message(response).await);
+    }
+    response.json::<Page>().await.map_err(|e| e.to_string())
+}
+
+// Clients talk to the first `listen` entry; there is always at least one.
+fn daemon_address(config: &AppConfig) -> Result<ListenAddress, String> {
+    Ok(config.listen_addresses()?.remove(0))
+}
+
+fn daemon_url(config: &AppConfig, path: &str) -> Result<String, String> {
+    let authority = match daemon_address(config)? {
+        ListenAddress::Tcp(address) => {
+            let ip = match address.ip() {
+                IpAddr::V4(a) if a.is_unspecified() => IpAddr::from([127, 0, 0, 1]),
+                IpAddr::V6(a) if a.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+                ip => ip,
+            };
+            SocketAddr::new(ip, address.port()).to_string()
+        }
+        // Only used for the Host header; the connection goes to the socket.
+        ListenAddress::Unix(_) => "localhost".to_string(),
+    };
+    let scheme = if config.tls.is_some() {
+        "https"
+    } else {
+        "http"
+    };
+    Ok(format!("{scheme}://{authority}/api/v1/{path}"))
+}
+
+fn http_client(config: &AppConfig) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(3));
+    if let ListenAddress::Unix(path) = daemon_address(config)? {
+        builder = builder.unix_socket(path);
+    }
+    // The daemon is addressed by IP, which a self-signed certificate rarely
+    // lists; trusting its certificate file is what matters here.
+    if let Some(tls) = &config.tls {
+        let pem = fs::read(&tls.cert_path).map_err(|e| e.to_string())?;
+        for cert in reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| e.to_string())? {
+            builder = builder.add_root_certificate(cert);
+        }
+        builder = builder.danger_accept_invalid_hostnames(true);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+async fn error_message(response: reqwest::Response) -> String {
+    let status = response.status();
+    response
+        .json::<serde_json::Value>()
+        .await
+        .ok()
+        .and_then(|body| body["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("HTTP {}", status))
+}
+
+async fn post_to_daemon(
+    notification: &AdhocNotification,
+    config: &AppConfig,
+) -> Result<(), DaemonError> {
+    let url = daemon_url(config, "notify").map_err(DaemonError::Unreachable)?;
+    let client = http_client(config).map_err(DaemonError::Unreachable)?;
+    let mut request = client.post(&url).json(notification);
+    if let Some(token) = &config.api_token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| DaemonError::Unreachable(e.to_string()))?;
+    if response.status().is_success() {
+        return Ok(());
+    }
+    Err(DaemonError::Rejected(error_message(response).await))
+}
@@ -0,0 +1,172 @@
+use crate::{i18n::tr, model::Interval, MAX_MESSAGE_LENGTH};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+const SAFE_PATH: &str = "/usr/local/bin:/usr/bin:/bin";
+const PASSTHROUGH_ENV: &[&str] = &[
+    "HOME",
+    "USER",
+    "LANG",
+    "LC_ALL",
+    "DISPLAY",
+    "XDG_RUNTIME_DIR",
+];
+// Variables that make the dynamic loader, libc or an interpreter load code
+// from elsewhere. Names ending in `PATH` are rejected as well.
+const LOADER_ENV: &[&str] = &[
+    "GLIBC_TUNABLES",
+    "HOSTALIASES",
+    "LOCALDOMAIN",
+    "MALLOC_TRACE",
+    "RES_OPTIONS",
+    "BASH_ENV",
+    "ENV",
+    "NODE_OPTIONS",
+    "PERL5LIB",
+    "PERL5OPT",
+    "PYTHONHOME",
+    "PYTHONSTARTUP",
+    "RUBYLIB",
+    "RUBYOPT",
+];
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct CommandSource {
+    pub(crate) program: PathBuf,
+    #[serde(default)]
+    pub(crate) args: Vec<String>,
+    #[serde(default)]
+    pub(crate) env: HashMap<String, String>,
+    pub(crate) timeout: Option<Interval>,
+}
+
+impl CommandSource {
+    fn timeout(&self) -> Duration {
+        self.timeout
+            .map(Interval::duration)
+            .unwrap_or(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+    }
+
+    // Only absolute paths that resolve to an entry of `command_allowlist` may run.
+    pub(crate) fn check_allowed(&self, allowlist: &[PathBuf]) -> Result<PathBuf, String> {
+        resolve_allowed(&self.program, allowlist)
+            .ok_or_else(|| tr!(CommandNotAllowed, program = self.program.display()))
+    }
+
+    // Returns `Ok(None)` when the command asks not to notify this time,
+    // i.e. on a non-zero exit status or empty output.
+    pub(crate) fn run(&self, allowlist: &[PathBuf]) -> Result<Option<String>, String> {
+        let program = self.check_allowed(allowlist)?;
+        let timeout = self.timeout();
+
+        let mut command = Command::new(&program);
+        command
+            .args(&self.args)
+            .env_clear()
+            .env("PATH", SAFE_PATH)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+        for var in PASSTHROUGH_ENV {
+            if let Ok(value) = std::env::var(var) {
+                command.env(var, value);
+            }
+        }
+        for (name, value) in &self.env {
+            if !is_allowed_env(name) {
+                return Err(tr!(ValidateEnvName, name = name));
+            }
+            command.env(name, value);
+        }
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| tr!(CommandSpawnFailed, program = program.display(), error = e))?;
+
+        let stdout = child.stdout.take();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut output = Vec::new();
+            if let Some(mut stdout) = stdout {
+                let _ = (&mut stdout)
+                    .take(MAX_MESSAGE_LENGTH as u64)
+                    .read_to_end(&mut output);
+                // Keep draining so a chatty command doesn't block on a full pipe.
+                let _ = std::io::copy(&mut stdout, &mut std::io::sink());
+            }
+            let _ = sender.send(output);
+        });
+
+        let timed_out = || {
+            tr!(
+                CommandTimedOut,
+                program = program.display(),
+                secs = timeout.as_secs(),
+            )
+        };
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(timed_out());
+                }
+                Ok(None) => thread::sleep(Duration::from_millis(50)),
+                Err(e) => {
+                    return Err(tr!(
+                        CommandSpawnFailed,
+                        program = program.display(),
+                        error = e
+                    ));
+                }
+            }
+        };
+
+        // A process the command left in the background may hold stdout open
+        // long after the command itself exited.
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let output = match receiver.recv_timeout(remaining) {
+            Ok(output) => output,
+            Err(RecvTimeoutError::Timeout) => return Err(timed_out()),
+            Err(RecvTimeoutError::Disconnected) => Vec::new(),
+        };
+        if !status.success() {
+            return Ok(None);
+        }
+        let output = String::from_utf8_lossy(&output).trim().to_string();
+        Ok((!output.is_empty()).then_some(output))
+    }
+}
+
+// Names a command's `env` may set; the rest would let a config change what
+// the allowlisted program actually runs.
+pub(crate) fn is_allowed_env(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.starts_with("LD_")
+        && !name.ends_with("PATH")
+        && !LOADER_ENV.contains(&name)
+}
+
+fn resolve_allowed(program: &Path, allowlist: &[PathBuf]) -> Option<PathBuf> {
+    if !program.is_absolute() {
+        return None;
+    }
+    let resolved = program.canonicalize().ok()?;
+    allowlist
+        .iter()
+        .filter_map(|entry| entry.canonicalize().ok())
+        .any(|entry| entry == resolved)
+        .then_some(resolved)
+}
@@ -0,0 +1,277 @@
+use crate::{
+    api,
+    i18n::tr,
+    model::Urgency,
+    validate::{self, Validate, ValidationErrors},
+    AdhocNotification,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use warp::{
+    http::{header, HeaderMap, Method},
+    hyper::body::Bytes,
+    reply::{self, Response},
+    Filter, Rejection, Reply,
+};
+
+// ntfy topics that would shadow pushel's own routes.
+const RESERVED_TOPICS: &[&str] = &["api", "metrics", "healthz", "readyz", "message", "1"];
+
+// Passes only when the compatibility route is enabled, so that disabled routes
+// answer like any unknown path.
+pub(crate) fn enabled(enabled: bool) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::any()
+        .and_then(move || async move {
+            if enabled {
+                Ok(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+        .untuple_one()
+}
+
+fn header<'a>(headers: &'a HeaderMap, names: &[&str]) -> Option<&'a str> {
+    names
+        .iter()
+        .find_map(|name| headers.get(*name)?.to_str().ok())
+}
+
+fn bearer(headers: &HeaderMap) -> Option<&str> {
+    header(headers, &[header::AUTHORIZATION.as_str()])?.strip_prefix("Bearer ")
+}
+
+fn check(notification: AdhocNotification) -> Result<AdhocNotification, ValidationErrors> {
+    notification.validate()?;
+    Ok(notification)
+}
+
+fn adhoc(title: Option<String>, message: String, urgency: Option<Urgency>) -> AdhocNotification {
+    AdhocNotification {
+        title,
+        message,
+        urgency,
+        expire_time: None,
+        app_name: None,
+        icon: None,
+        category: None,
+        transient: None,
+        dedup_key: None,
+        variables: HashMap::new(),
+    }
+}
+
+fn invalid_priority(value: impl ToString) -> ValidationErrors {
+    ValidationErrors::single(
+        "priority",
+        "invalid",
+        tr!(ValidatePriority, value = value.to_string()),
+    )
+}
+
+// Gotify and Pushover clients send either form or JSON bodies.
+fn parse_body<T: for<'de> Deserialize<'de>>(
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<T, ValidationErrors> {
+    let form = header(headers, &[header::CONTENT_TYPE.as_str()])
+        .is_some_and(|t| t.starts_with("application/x-www-form-urlencoded"));
+    if form {
+        serde_urlencoded::from_bytes(body)
+            .map_err(|e| ValidationErrors::single("body", "invalid", e.to_string()))
+    } else {
+        validate::parse_json(body)
+    }
+}
+
+// ntfy: `PUT/POST /<topic>` with the message as plain-text body and options
+// in headers, see https://docs.ntfy.sh/publish/. Any other request answers
+// like an unknown path rather than with 405, as the route matches every
+// top-level path.
+pub(crate) fn ntfy_topic() -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::path::param::<String>()
+        .and(warp::path::end())
+        .and(warp::method())
+        .and_then(|topic: String, method: Method| async move {
+            let valid = (method == Method::PUT || method == Method::POST)
+                && (1..=64).contains(&topic.len())
+                && topic
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                && !RESERVED_TOPICS.contains(&topic.as_str());
+            if valid {
+                Ok(topic)
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+}
+
+// ntfy clients trigger with an empty POST that may lack `Content-Length`;
+// everything else is limited like the native API.
+pub(crate) fn ntfy_body() -> impl Filter<Extract = (Bytes,), Error = Rejection> + Clone {
+    let empty = warp::header::headers_cloned().and_then(|headers: HeaderMap| async move {
+        if headers.contains_key(header::CONTENT_LENGTH)
+            || headers.contains_key(header::TRANSFER_ENCODING)
+        {
+            Err(warp::reject::not_found())
+        } else {
+            Ok(Bytes::new())
+        }
+    });
+    warp::body::content_length_limit(api::MAX_BODY_BYTES)
+        .and(warp::body::bytes())
+        .or(empty)
+        .unify()
+}
+
+// Tokens are sent as bearer token or as the password of basic auth.
+pub(crate) fn ntfy_secret(headers: &HeaderMap) -> Option<String> {
+    if let Some(token) = bearer(headers) {
+        return Some(token.to_string());
+    }
+    let encoded = header(headers, &[header::AUTHORIZATION.as_str()])?.strip_prefix("Basic ")?;
+    let decoded = String::from_utf8(STANDARD.decode(encoded).ok()?).ok()?;
+    let (_, password) = decoded.split_once(':')?;
+    Some(password.to_string())
+}
+
+pub(crate) fn ntfy(
+    topic: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<AdhocNotification, ValidationErrors> {
+    let message = match header(headers, &["x-message", "message", "m"]) {
+        Some(message) => message.to_string(),
+        None => String::from_utf8(body.to_vec())
+            .map_err(|_| ValidationErrors::single("message", "invalid", tr!(ValidateUtf8)))?,
+    };
+    // Like ntfy itself: an empty body still triggers, and the topic is the
+    // fallback title.
+    let message = if message.is_empty() {
+        tr!(NtfyTriggered).to_string()
+    } else {
+        message
+    };
+    let title = header(headers, &["x-title", "title", "ti", "t"]).unwrap_or(topic);
+    let urgency = header(headers, &["x-priority", "priority", "prio", "p"])
+        .map(|priority| match priority.to_ascii_lowercase().as_str() {
+            "1" | "min" | "2" | "low" => Ok(Urgency::Low),
+            "3" | "default" => Ok(Urgency::Normal),
+            "4" | "high" | "5" | "max" | "urgent" => Ok(Urgency::Critical),
+            _ => Err(invalid_priority(priority)),
+        })
+        .transpose()?;
+    check(adhoc(Some(title.to_string()), message, urgency))
+}
+
+pub(crate) fn ntfy_reply(topic: &str, notification: &AdhocNotification) -> Response {
+    reply::json(&serde_json::json!({
+        "id": format!("{:012x}", rand::random::<u64>() >> 16),
+        "time": Utc::now().timestamp(),
+        "event": "message",
+        "topic": topic,
+        "title": notification.title,
+        "message": notification.message,
+    }))
+    .into_response()
+}
+
+// Gotify: `POST /message`, see https://gotify.net/api-docs.
+#[derive(Debug, Deserialize)]
+pub(crate) struct GotifyMessage {
+    title: Option<String>,
+    message: String,
+    priority: Option<i64>,
+}
+
+pub(crate) fn gotify_secret(
+    headers: &HeaderMap,
+    query: &HashMap<String, String>,
+) -> Option<String> {
+    header(headers, &["x-gotify-key"])
+        .or_else(|| query.get("token").map(String::as_str))
+        .or_else(|| bearer(headers))
+        .map(str::to_string)
+}
+
+pub(crate) fn gotify(headers: &HeaderMap, body: &[u8]) -> Result<GotifyMessage, ValidationErrors> {
+    parse_body(headers, body)
+}
+
+impl GotifyMessage {
+    pub(crate) fn priority(&self) -> i64 {
+        self.priority.unwrap_or(0)
+    }
+
+    pub(crate) fn notification(self) -> Result<AdhocNotification, ValidationErrors> {
+        let urgency = match self.priority {
+            None => None,
+            Some(0..=3) => Some(Urgency::Low),
+            Some(4..=7) => Some(Urgency::Normal),
+            Some(8..=10) => Some(Urgency::Critical),
+            Some(priority) => return Err(invalid_priority(priority)),
+        };
+        check(adhoc(self.title, self.message, urgency))
+    }
+}
+
+pub(crate) fn gotify_reply(notification: &AdhocNotification, priority: i64) -> Response {
+    reply::json(&serde_json::json!({
+        "appid": 0,
+        "title": notification.title,
+        "message": notification.message,
+        "priority": priority,
+        "date": Utc::now().to_rfc3339(),
+    }))
+    .into_response()
+}
+
+// Pushover: `POST /1/messages.json`, see https://pushover.net/api. The
+// application token doubles as API token; `user` is ignored.
+#[derive(Debug, Deserialize)]
+pub(crate) struct PushoverMessage {
+    title: Option<String>,
+    message: String,
+    priority: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct PushoverToken {
+    token: Option<String>,
+}
+
+// Read on its own so that an invalid message is still reported as such.
+pub(crate) fn pushover_secret(headers: &HeaderMap, body: &[u8]) -> Option<String> {
+    parse_body::<PushoverToken>(headers, body).ok()?.token
+}
+
+pub(crate) fn pushover(
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<PushoverMessage, ValidationErrors> {
+    parse_body(headers, body)
+}
+
+impl PushoverMessage {
+    pub(crate) fn notification(self) -> Result<AdhocNotification, ValidationErrors> {
+        let urgency = match self.priority {
+            None => None,
+            Some(-2..=-1) => Some(Urgency::Low),
+            Some(0) => Some(Urgency::Normal),
+            Some(1..=2) => Some(Urgency::Critical),
+            Some(priority) => return Err(invalid_priority(priority)),
+        };
+        check(adhoc(self.title, self.message, urgency))
+    }
+}
+
+pub(crate) fn pushover_reply() -> Response {
+    reply::json(&serde_json::json!({
+        "status": 1,
+        "request": format!("{:032x}", rand::random::<u128>()),
+    }))
+    .into_response()
+}
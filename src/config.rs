@@ -0,0 +1,470 @@
+use crate::{
+    auth::Scope,
+    i18n::{t, tr, Msg},
+    listen::ListenAddress,
+    model::{Interval, Urgency},
+    validate::{Validate, ValidationErrors},
+    NotificationConfig,
+};
+use chrono::{DateTime, FixedOffset};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+use std::{
+    fs,
+    net::{IpAddr, SocketAddr},
+    path::{Path, PathBuf},
+};
+
+pub(crate) const CONFIG_VERSION: u32 = 2;
+pub(crate) const NOTIFICATIONS_VERSION: u32 = 1;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct AppConfig {
+    pub(crate) listen_address: String,
+    pub(crate) port: u16,
+    // Replaces `listen_address` and `port` when set.
+    #[serde(default)]
+    pub(crate) listen: Vec<ListenAddress>,
+    pub(crate) webserver_enabled: bool,
+    pub(crate) log_format: String,
+    #[serde(default)]
+    pub(crate) api_token: Option<String>,
+    #[serde(default)]
+    pub(crate) api_tokens: Vec<ApiTokenConfig>,
+    #[serde(default)]
+    pub(crate) webhook_senders: Vec<WebhookSenderConfig>,
+    #[serde(default = "default_webhook_replay_window")]
+    pub(crate) webhook_replay_window: Interval,
+    #[serde(default = "default_rate_limit_rpm")]
+    pub(crate) rate_limit_rpm: u32,
+    // Reverse proxies whose `X-Forwarded-For` header is believed.
+    #[serde(default)]
+    pub(crate) trusted_proxies: Vec<IpAddr>,
+    pub(crate) homeassistant_url: Option<String>,
+    pub(crate) homeassistant_api_key: Option<String>,
+    #[serde(default)]
+    pub(crate) language: Option<String>,
+    #[serde(default)]
+    pub(crate) command_allowlist: Vec<PathBuf>,
+    #[serde(default)]
+    pub(crate) defaults: NotificationDefaults,
+    #[serde(default)]
+    pub(crate) coalesce_window: Option<Interval>,
+    #[serde(default = "default_dedup_ttl")]
+    pub(crate) dedup_ttl: Interval,
+    #[serde(default = "default_history_size")]
+    pub(crate) history_size: usize,
+    #[serde(default)]
+    pub(crate) tls: Option<TlsConfig>,
+    #[serde(default)]
+    pub(crate) compat: CompatConfig,
+}
+
+impl AppConfig {
+    // `listen`, or `listen_address` and `port` for configs without it.
+    pub(crate) fn listen_addresses(&self) -> Result<Vec<ListenAddress>, String> {
+        if !self.listen.is_empty() {
+            return Ok(self.listen.clone());
+        }
+        let ip = self.listen_address.parse::<IpAddr>().map_err(|e| {
+            tr!(
+                InvalidListenAddress,
+                address = self.listen_address,
+                error = e
+            )
+        })?;
+        Ok(vec![ListenAddress::Tcp(SocketAddr::new(ip, self.port))])
+    }
+}
+
+fn default_rate_limit_rpm() -> u32 {
+    60
+}
+
+fn default_webhook_replay_window() -> Interval {
+    Interval::from_secs(5 * 60)
+}
+
+fn default_dedup_ttl() -> Interval {
+    Interval::from_secs(5 * 60)
+}
+
+fn default_history_size() -> usize {
+    500
+}
+
+// Serve HTTPS instead of HTTP. PEM files; the certificate file may contain
+// the whole chain.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct TlsConfig {
+    pub(crate) cert_path: PathBuf,
+    pub(crate) key_path: PathBuf,
+    // Require client certificates issued by this CA bundle (mutual TLS).
+    #[serde(default)]
+    pub(crate) client_ca_path: Option<PathBuf>,
+}
+
+// Routes that accept the formats of other notification services, so their
+// clients can send to pushel unchanged.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(crate) struct CompatConfig {
+    // `PUT/POST /<topic>`
+    #[serde(default)]
+    pub(crate) ntfy: bool,
+    // `POST /message`
+    #[serde(default)]
+    pub(crate) gotify: bool,
+    // `POST /1/messages.json`
+    #[serde(default)]
+    pub(crate) pushover: bool,
+}
+
+// A named API token; create one with `pushel token`.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ApiTokenConfig {
+    pub(crate) name: String,
+    // `sha256:` followed by the hex digest of the secret.
+    pub(crate) hash: String,
+    pub(crate) scopes: Vec<Scope>,
+    #[serde(default)]
+    pub(crate) expires_at: Option<DateTime<FixedOffset>>,
+    // Per-token requests per minute instead of the per-address `rate_limit_rpm`.
+    #[serde(default)]
+    pub(crate) rate_limit_rpm: Option<u32>,
+}
+
+// A service that signs its requests instead of sending a bearer token.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct WebhookSenderConfig {
+    pub(crate) name: String,
+    // Shared HMAC-SHA256 key.
+    pub(crate) secret: String,
+}
+
+pub(crate) const DEFAULT_TITLE: &str = "Erinnerung";
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct NotificationDefaults {
+    #[serde(default = "default_title")]
+    pub(crate) title: String,
+    pub(crate) urgency: Option<Urgency>,
+    pub(crate) expire_time: Option<u32>,
+    pub(crate) app_name: Option<String>,
+    pub(crate) icon: Option<String>,
+    pub(crate) category: Option<String>,
+    pub(crate) transient: Option<bool>,
+}
+
+fn default_title() -> String {
+    DEFAULT_TITLE.to_string()
+}
+
+impl Default for NotificationDefaults {
+    fn default() -> Self {
+        NotificationDefaults {
+            title: default_title(),
+            urgency: None,
+            expire_time: None,
+            app_name: None,
+            icon: None,
+            category: None,
+            transient: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct NotificationsFile {
+    pub(crate) notifications: Vec<NotificationConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    pub(crate) fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => ConfigFormat::Toml,
+            Some("yaml") | Some("yml") => ConfigFormat::Yaml,
+            _ => ConfigFormat::Json,
+        }
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["toml", "yaml", "yml", "json"]
+    }
+
+    fn parse(self, data: &str) -> Result<Value, String> {
+        match self {
+            ConfigFormat::Json => serde_json::from_str(data).map_err(|e| e.to_string()),
+            ConfigFormat::Toml => toml::from_str(data).map_err(|e| e.to_string()),
+            ConfigFormat::Yaml => serde_yaml_ng::from_str(data).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn serialize(self, value: &Value) -> Result<String, String> {
+        match self {
+            ConfigFormat::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+            ConfigFormat::Toml => {
+                let mut value = value.clone();
+                strip_nulls(&mut value);
+                toml::to_string_pretty(&value).map_err(|e| e.to_string())
+            }
+            ConfigFormat::Yaml => serde_yaml_ng::to_string(value).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+// TOML has no null, so optional keys set to null are simply left out.
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+pub(crate) fn find_config_file(dir: &Path, stem: &str) -> PathBuf {
+    ConfigFormat::extensions()
+        .iter()
+        .map(|ext| dir.join(format!("{stem}.{ext}")))
+        .find(|p| p.exists())
+        .unwrap_or_else(|| dir.join(format!("{stem}.json")))
+}
+
+type Migration = fn(&mut Value);
+
+struct Schema {
+    name: &'static str,
+    version: u32,
+    // migrations[n] upgrades a version n document to version n + 1
+    migrations: &'static [Migration],
+    deprecated: &'static [(&'static str, Msg)],
+}
+
+const CONFIG_SCHEMA: Schema = Schema {
+    name: "config",
+    version: CONFIG_VERSION,
+    migrations: &[migrate_config_v0, migrate_config_v1],
+    deprecated: &[("default_title", Msg::ConfigHintDefaultTitle)],
+};
+
+const NOTIFICATIONS_SCHEMA: Schema = Schema {
+    name: "notifications",
+    version: NOTIFICATIONS_VERSION,
+    migrations: &[migrate_notifications_v0],
+    deprecated: &[],
+};
+
+fn migrate_config_v0(_value: &mut Value) {}
+
+fn migrate_config_v1(value: &mut Value) {
+    let Some(map) = value.as_object_mut() else {
+        return;
+    };
+    if let Some(title) = map.shift_remove("default_title") {
+        let defaults = map
+            .entry("defaults")
+            .or_insert_with(|| serde_json::json!({}));
+        if let Some(defaults) = defaults.as_object_mut() {
+            defaults.entry("title").or_insert(title);
+        }
+    }
+}
+
+fn migrate_notifications_v0(value: &mut Value) {
+    if value.is_array() {
+        *value = serde_json::json!({ "notifications": value.take() });
+    }
+}
+
+// Files from before versioning have no `version` and count as version 0.
+fn document_version(value: &Value) -> Option<u32> {
+    match value.get("version") {
+        None => Some(0),
+        Some(version) => version.as_u64().and_then(|v| u32::try_from(v).ok()),
+    }
+}
+
+pub(crate) struct Loaded<T> {
+    pub(crate) value: T,
+    pub(crate) warnings: Vec<String>,
+}
+
+// Migrations happen in memory; the file is only backed up and rewritten once
+// the migrated document has loaded and validated.
+fn load<T: DeserializeOwned>(
+    path: &Path,
+    schema: &Schema,
+    persist_migration: bool,
+    validate: impl FnOnce(&T) -> Result<(), ValidationErrors>,
+) -> Result<Loaded<T>, String> {
+    let format = ConfigFormat::from_path(path);
+    let display = path.display();
+    let data = fs::read_to_string(path).map_err(|e| {
+        tr!(
+            ConfigReadFailed,
+            kind = schema.name,
+            path = display,
+            error = e
+        )
+    })?;
+    let mut value = format.parse(&data).map_err(|e| {
+        tr!(
+            ConfigParseFailed,
+            kind = schema.name,
+            path = display,
+            error = e
+        )
+    })?;
+    let mut warnings = Vec::new();
+
+    let original_version = document_version(&value).ok_or_else(|| {
+        tr!(
+            ConfigVersionInvalid,
+            kind = schema.name,
+            path = display,
+            version = value["version"],
+        )
+    })?;
+    if original_version > schema.version {
+        return Err(tr!(
+            ConfigVersionTooNew,
+            kind = schema.name,
+            path = display,
+            version = original_version,
+            supported = schema.version,
+        ));
+    }
+    for version in original_version..schema.version {
+        (schema.migrations[version as usize])(&mut value);
+    }
+    if let Value::Object(map) = &mut value {
+        let mut versioned = serde_json::Map::new();
+        versioned.insert("version".to_string(), Value::from(schema.version));
+        versioned.extend(
+            std::mem::take(map)
+                .into_iter()
+                .filter(|(k, _)| k != "version"),
+        );
+        *map = versioned;
+    }
+
+    let migrated = (original_version < schema.version).then(|| value.clone());
+
+    if let Value::Object(map) = &mut value {
+        map.remove("version");
+    }
+
+    let mut track_ignored = |key: serde_ignored::Path| {
+        let key = key.to_string();
+        match schema.deprecated.iter().find(|(old, _)| *old == key) {
+            Some((_, hint)) => warnings.push(tr!(
+                ConfigDeprecatedKey,
+                key = key,
+                kind = schema.name,
+                path = display,
+                hint = t(*hint),
+            )),
+            None => warnings.push(tr!(
+                ConfigUnknownKey,
+                key = key,
+                kind = schema.name,
+                path = display,
+            )),
+        }
+    };
+    let parsed: T = serde_path_to_error::deserialize(serde_ignored::Deserializer::new(
+        value,
+        &mut track_ignored,
+    ))
+    .map_err(|e| invalid(path, schema, e.into()))?;
+    validate(&parsed).map_err(|e| invalid(path, schema, e))?;
+
+    if let Some(migrated) = migrated {
+        let warning = if persist_migration {
+            let backup = path.with_extension(format!(
+                "{}.v{original_version}.bak",
+                path.extension().and_then(|e| e.to_str()).unwrap_or("json")
+            ));
+            fs::copy(path, &backup)
+                .map_err(|e| tr!(ConfigBackupFailed, path = display, error = e))?;
+            let migrated = format.serialize(&migrated)?;
+            fs::write(path, migrated)
+                .map_err(|e| tr!(ConfigWriteFailed, path = display, error = e))?;
+            tr!(
+                ConfigMigrated,
+                kind = schema.name,
+                path = display,
+                from = original_version,
+                to = schema.version,
+                backup = backup.display(),
+            )
+        } else {
+            tr!(
+                ConfigMigrationPending,
+                kind = schema.name,
+                path = display,
+                from = original_version,
+                to = schema.version,
+            )
+        };
+        warnings.insert(0, warning);
+    }
+
+    Ok(Loaded {
+        value: parsed,
+        warnings,
+    })
+}
+
+fn invalid(path: &Path, schema: &Schema, errors: ValidationErrors) -> String {
+    tr!(
+        ConfigInvalid,
+        kind = schema.name,
+        path = path.display(),
+        error = errors
+    )
+}
+
+pub(crate) fn load_app_config(
+    path: &Path,
+    persist_migration: bool,
+) -> Result<Loaded<AppConfig>, String> {
+    load(path, &CONFIG_SCHEMA, persist_migration, AppConfig::validate)
+}
+
+// Commands are checked against `allowlist`, the app config's `command_allowlist`.
+pub(crate) fn load_notifications(
+    path: &Path,
+    persist_migration: bool,
+    allowlist: &[PathBuf],
+) -> Result<Loaded<Vec<NotificationConfig>>, String> {
+    let loaded: Loaded<NotificationsFile> = load(
+        path,
+        &NOTIFICATIONS_SCHEMA,
+        persist_migration,
+        |file: &NotificationsFile| file.validate_with(allowlist),
+    )?;
+    let mut notifications = loaded.value.notifications;
+    // Unnamed reminders are identified by their position in the file.
+    for (i, notification) in notifications.iter_mut().enumerate() {
+        notification.name.get_or_insert_with(|| i.to_string());
+    }
+    Ok(Loaded {
+        value: notifications,
+        warnings: loaded.warnings,
+    })
+}
+
+pub(crate) fn render_default(path: &Path, json: &str) -> Result<String, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    ConfigFormat::from_path(path).serialize(&value)
+}
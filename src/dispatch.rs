@@ -0,0 +1,209 @@
+use crate::{
+    history::{History, Outcome, Source},
+    i18n::tr,
+    model::Notification,
+    send_notification, MAX_MESSAGE_LENGTH,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+struct Recent {
+    sent: Instant,
+    content: (String, String),
+    id: Option<u32>,
+}
+
+#[derive(Default)]
+struct State {
+    pending: Vec<(Notification, Source)>,
+    recent: HashMap<String, Recent>,
+}
+
+// Sits in front of `send_notification`: merges bursts into one summary bubble
+// and suppresses repeats of keyed ad-hoc notifications. Every outcome ends up
+// in the history.
+pub(crate) struct Dispatcher {
+    state: Arc<Mutex<State>>,
+    history: History,
+    window: Option<Duration>,
+    dedup_ttl: Duration,
+}
+
+impl Dispatcher {
+    pub(crate) fn new(window: Option<Duration>, dedup_ttl: Duration, history: History) -> Self {
+        Dispatcher {
+            state: Arc::new(Mutex::new(State::default())),
+            history,
+            window,
+            dedup_ttl,
+        }
+    }
+
+    // Returns `false` when a keyed repeat was suppressed as a duplicate.
+    pub(crate) fn submit(
+        &self,
+        notification: Notification,
+        source: Source,
+        dedup_key: Option<&str>,
+    ) -> bool {
+        if let Some(key) = dedup_key {
+            return self.submit_keyed(notification, source, key);
+        }
+        let Some(window) = self.window else {
+            send(&notification, source, &self.history);
+            return true;
+        };
+
+        let first = {
+            let mut state = self.state.lock().expect("dispatcher mutex poisoned");
+            state.pending.push((notification, source));
+            state.pending.len() == 1
+        };
+        if first {
+            let state = Arc::clone(&self.state);
+            let history = self.history.clone();
+            thread::spawn(move || {
+                thread::sleep(window);
+                let pending =
+                    std::mem::take(&mut state.lock().expect("dispatcher mutex poisoned").pending);
+                flush(pending, &history);
+            });
+        }
+        true
+    }
+
+    // For notifications that were due but deliberately not shown.
+    pub(crate) fn suppress(&self, notification: &Notification, source: Source, reason: &str) {
+        self.history.record(
+            source,
+            Outcome::Suppressed,
+            Some(reason.to_string()),
+            notification,
+        );
+    }
+
+    // Keyed notifications skip coalescing so that each key keeps its own
+    // bubble, which a changed repeat can then update in place. The key is
+    // claimed under the lock, the slow send happens after releasing it.
+    fn submit_keyed(&self, mut notification: Notification, source: Source, key: &str) -> bool {
+        let now = Instant::now();
+        let content = (notification.title.clone(), notification.message.clone());
+        let duplicate = {
+            let mut state = self.state.lock().expect("dispatcher mutex poisoned");
+            state
+                .recent
+                .retain(|_, recent| now.duration_since(recent.sent) < self.dedup_ttl);
+            match state.recent.get(key) {
+                Some(recent) if recent.content == content => true,
+                recent => {
+                    if let Some(recent) = recent {
+                        notification.replaces_id = recent.id;
+                    }
+                    state.recent.insert(
+                        key.to_string(),
+                        Recent {
+                            sent: now,
+                            content,
+                            id: notification.replaces_id,
+                        },
+                    );
+                    false
+                }
+            }
+        };
+        if duplicate {
+            info!("{}", tr!(NotificationDeduplicated, key = key));
+            self.suppress(&notification, source, "duplicate");
+            return false;
+        }
+
+        if let Some(id) = send(&notification, source, &self.history).flatten() {
+            let mut state = self.state.lock().expect("dispatcher mutex poisoned");
+            // Unless a newer submission for the key took over meanwhile.
+            if let Some(recent) = state.recent.get_mut(key).filter(|r| r.sent == now) {
+                recent.id = Some(id);
+            }
+        }
+        true
+    }
+}
+
+impl Clone for Dispatcher {
+    fn clone(&self) -> Self {
+        Dispatcher {
+            state: Arc::clone(&self.state),
+            history: self.history.clone(),
+            window: self.window,
+            dedup_ttl: self.dedup_ttl,
+        }
+    }
+}
+
+fn send(notification: &Notification, source: Source, history: &History) -> Option<Option<u32>> {
+    match send_notification(notification) {
+        Ok(id) => {
+            history.record(source, Outcome::Delivered, None, notification);
+            Some(id)
+        }
+        Err(e) => {
+            history.record(source, Outcome::Failed, Some(e), notification);
+            None
+        }
+    }
+}
+
+// Merged notifications are recorded individually, each with the outcome of
+// the summary that carried them.
+fn flush(mut pending: Vec<(Notification, Source)>, history: &History) {
+    match pending.len() {
+        0 => {}
+        1 => {
+            let (notification, source) = pending.remove(0);
+            send(&notification, source, history);
+        }
+        count => {
+            info!("{}", tr!(NotificationsCoalescedLog, count = count));
+            let notifications: Vec<Notification> = pending.iter().map(|(n, _)| n.clone()).collect();
+            let (outcome, reason) = match send_notification(&summarize(&notifications)) {
+                Ok(_) => (Outcome::Delivered, "merged".to_string()),
+                Err(e) => (Outcome::Failed, e),
+            };
+            for (notification, source) in pending {
+                history.record(source, outcome, Some(reason.clone()), &notification);
+            }
+        }
+    }
+}
+
+fn summarize(pending: &[Notification]) -> Notification {
+    let mut message = pending
+        .iter()
+        .map(|n| format!("{}: {}", n.title, n.message))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if message.len() > MAX_MESSAGE_LENGTH {
+        let mut cut = MAX_MESSAGE_LENGTH - '…'.len_utf8();
+        while !message.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        message.truncate(cut);
+        message.push('…');
+    }
+    let first = &pending[0];
+    Notification {
+        title: tr!(NotificationsCoalesced, count = pending.len()),
+        message,
+        urgency: pending.iter().filter_map(|n| n.urgency).max(),
+        expire_time: pending.iter().filter_map(|n| n.expire_time).max(),
+        app_name: first.app_name.clone(),
+        icon: first.icon.clone(),
+        category: None,
+        transient: pending.iter().all(|n| n.transient),
+        replaces_id: None,
+    }
+}
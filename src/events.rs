@@ -0,0 +1,69 @@
+use crate::history::Entry;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::convert::Infallible;
+use tokio::sync::broadcast;
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+
+// Slow subscribers that fall this far behind miss events rather than holding
+// up the daemon.
+const CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum Event {
+    // Same shape as a history entry: delivered, suppressed or failed.
+    Notification(Entry),
+    Motion {
+        status: String,
+        timestamp: DateTime<Local>,
+    },
+    // `next_at` is missing once a reminder has finished for good.
+    Schedule {
+        name: String,
+        title: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        next_at: Option<DateTime<Local>>,
+    },
+}
+
+impl Event {
+    fn name(&self) -> &'static str {
+        match self {
+            Event::Notification(_) => "notification",
+            Event::Motion { .. } => "motion",
+            Event::Schedule { .. } => "schedule",
+        }
+    }
+}
+
+// Fan-out of daemon events to `/api/v1/events` subscribers. Publishing never
+// blocks and is a no-op while nobody listens.
+#[derive(Clone)]
+pub(crate) struct Events {
+    sender: broadcast::Sender<Event>,
+}
+
+impl Events {
+    pub(crate) fn new() -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        Events { sender }
+    }
+
+    pub(crate) fn publish(&self, event: Event) {
+        let _ = self.sender.send(event);
+    }
+
+    pub(crate) fn sse_stream(
+        &self,
+    ) -> impl Stream<Item = Result<warp::sse::Event, Infallible>> + Send + 'static {
+        BroadcastStream::new(self.sender.subscribe()).filter_map(|event| {
+            let event = event.ok()?;
+            warp::sse::Event::default()
+                .event(event.name())
+                .json_data(&event)
+                .ok()
+                .map(Ok)
+        })
+    }
+}
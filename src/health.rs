@@ -0,0 +1,155 @@
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Component {
+    Config,
+    Scheduler,
+    Idle,
+    Notifications,
+    HomeAssistant,
+}
+
+// Ordered from best to worst; the overall status is the worst component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Status {
+    Disabled,
+    Ok,
+    Degraded,
+    Starting,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ComponentHealth {
+    pub(crate) status: Status,
+    pub(crate) updated_at: Option<DateTime<Local>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) last_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) last_error_at: Option<DateTime<Local>>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Report {
+    pub(crate) status: Status,
+    pub(crate) components: BTreeMap<Component, ComponentHealth>,
+}
+
+impl Report {
+    // Alive unless a subsystem is down.
+    pub(crate) fn healthy(&self) -> bool {
+        self.status != Status::Down
+    }
+
+    // Ready once everything has started; a degraded component still serves.
+    pub(crate) fn ready(&self) -> bool {
+        self.status <= Status::Degraded
+    }
+
+    // For callers without a valid token: statuses only.
+    pub(crate) fn redacted(mut self) -> Self {
+        for component in self.components.values_mut() {
+            component.last_error = None;
+        }
+        self
+    }
+}
+
+// Latest state of each subsystem for `/healthz` and `/readyz`.
+pub(crate) struct Health {
+    inner: Arc<Mutex<BTreeMap<Component, ComponentHealth>>>,
+}
+
+impl Health {
+    pub(crate) fn new() -> Self {
+        let starting = ComponentHealth {
+            status: Status::Starting,
+            updated_at: None,
+            last_error: None,
+            last_error_at: None,
+        };
+        let components = [
+            Component::Config,
+            Component::Scheduler,
+            Component::Idle,
+            Component::Notifications,
+            Component::HomeAssistant,
+        ]
+        .into_iter()
+        .map(|component| (component, starting.clone()))
+        .collect();
+        Health {
+            inner: Arc::new(Mutex::new(components)),
+        }
+    }
+
+    fn update(&self, component: Component, status: Option<Status>, error: Option<String>) {
+        let mut components = self.inner.lock().expect("health mutex poisoned");
+        let entry = components
+            .get_mut(&component)
+            .expect("all components are registered");
+        let now = Local::now();
+        // Only a success lifts a component out of `down`.
+        if let Some(status) = status {
+            if !(entry.status == Status::Down && status == Status::Degraded) {
+                entry.status = status;
+            }
+        }
+        entry.updated_at = Some(now);
+        if error.is_some() {
+            entry.last_error = error;
+            entry.last_error_at = Some(now);
+        }
+    }
+
+    pub(crate) fn ok(&self, component: Component) {
+        self.update(component, Some(Status::Ok), None);
+    }
+
+    pub(crate) fn disabled(&self, component: Component) {
+        self.update(component, Some(Status::Disabled), None);
+    }
+
+    pub(crate) fn degraded(&self, component: Component, error: impl fmt::Display) {
+        self.update(component, Some(Status::Degraded), Some(error.to_string()));
+    }
+
+    pub(crate) fn down(&self, component: Component, error: impl fmt::Display) {
+        self.update(component, Some(Status::Down), Some(error.to_string()));
+    }
+
+    // Keeps the status; for errors that affect a single reminder rather than
+    // the whole subsystem.
+    pub(crate) fn error(&self, component: Component, error: impl fmt::Display) {
+        self.update(component, None, Some(error.to_string()));
+    }
+
+    pub(crate) fn report(&self) -> Report {
+        let components = self.inner.lock().expect("health mutex poisoned").clone();
+        Report {
+            status: components
+                .values()
+                .map(|c| c.status)
+                .max()
+                .unwrap_or(Status::Ok)
+                .max(Status::Ok),
+            components,
+        }
+    }
+}
+
+impl Clone for Health {
+    fn clone(&self) -> Self {
+        Health {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
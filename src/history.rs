@@ -0,0 +1,233 @@
+use crate::{
+    events::{Event, Events},
+    health::{Component, Health},
+    metrics::Metrics,
+    model::{Notification, Urgency},
+};
+use chrono::{DateTime, FixedOffset, Local};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fmt,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Outcome {
+    Delivered,
+    Suppressed,
+    Failed,
+}
+
+impl Outcome {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Delivered => "delivered",
+            Outcome::Suppressed => "suppressed",
+            Outcome::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SourceKind {
+    Reminder,
+    Watch,
+    Api,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum Source {
+    Reminder {
+        name: String,
+    },
+    Watch {
+        name: String,
+    },
+    Api {
+        address: Option<IpAddr>,
+        // Name of the API token, if any are configured.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+    },
+}
+
+impl Source {
+    fn kind(&self) -> SourceKind {
+        match self {
+            Source::Reminder { .. } => SourceKind::Reminder,
+            Source::Watch { .. } => SourceKind::Watch,
+            Source::Api { .. } => SourceKind::Api,
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Reminder { name } => write!(f, "reminder ({name})"),
+            Source::Watch { name } => write!(f, "watch ({name})"),
+            Source::Api { address, token } => match (address, token) {
+                (Some(address), Some(token)) => write!(f, "api ({token}@{address})"),
+                (Some(address), None) => write!(f, "api ({address})"),
+                (None, Some(token)) => write!(f, "api ({token})"),
+                (None, None) => f.write_str("api"),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    pub(crate) id: u64,
+    pub(crate) timestamp: DateTime<Local>,
+    pub(crate) source: Source,
+    pub(crate) outcome: Outcome,
+    // Machine-readable for suppressions (`duplicate`, `away`, `daily_limit`),
+    // the error message for failures.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) reason: Option<String>,
+    pub(crate) title: String,
+    pub(crate) message: String,
+    pub(crate) urgency: Option<Urgency>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct Query {
+    outcome: Option<Outcome>,
+    source: Option<SourceKind>,
+    since: Option<DateTime<FixedOffset>>,
+    q: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Page {
+    pub(crate) total: usize,
+    pub(crate) offset: usize,
+    pub(crate) limit: usize,
+    pub(crate) entries: Vec<Entry>,
+}
+
+struct Ring {
+    entries: VecDeque<Entry>,
+    next_id: u64,
+    capacity: usize,
+}
+
+// Bounded in-memory record of what happened to each notification; the oldest
+// entries are dropped once `capacity` is reached. Every entry is also
+// published as an event, counted in the metrics and reflected in the health
+// of the notification backend.
+pub(crate) struct History {
+    inner: Arc<Mutex<Ring>>,
+    events: Events,
+    metrics: Metrics,
+    health: Health,
+}
+
+impl History {
+    pub(crate) fn new(capacity: usize, events: Events, metrics: Metrics, health: Health) -> Self {
+        History {
+            inner: Arc::new(Mutex::new(Ring {
+                entries: VecDeque::with_capacity(capacity),
+                next_id: 1,
+                capacity,
+            })),
+            events,
+            metrics,
+            health,
+        }
+    }
+
+    pub(crate) fn record(
+        &self,
+        source: Source,
+        outcome: Outcome,
+        reason: Option<String>,
+        notification: &Notification,
+    ) {
+        self.metrics.notification(&source, outcome);
+        match (outcome, &reason) {
+            (Outcome::Delivered, _) => self.health.ok(Component::Notifications),
+            (Outcome::Failed, Some(error)) => self.health.degraded(Component::Notifications, error),
+            _ => {}
+        }
+        let mut ring = self.inner.lock().expect("history mutex poisoned");
+        let entry = Entry {
+            id: ring.next_id,
+            timestamp: Local::now(),
+            source,
+            outcome,
+            reason,
+            title: notification.title.clone(),
+            message: notification.message.clone(),
+            urgency: notification.urgency,
+        };
+        ring.next_id += 1;
+        self.events.publish(Event::Notification(entry.clone()));
+        if ring.capacity == 0 {
+            return;
+        }
+        if ring.entries.len() == ring.capacity {
+            ring.entries.pop_front();
+        }
+        ring.entries.push_back(entry);
+    }
+
+    // Newest first.
+    pub(crate) fn query(&self, query: &Query) -> Page {
+        let needle = query.q.as_deref().map(str::to_lowercase);
+        let ring = self.inner.lock().expect("history mutex poisoned");
+        let matching: Vec<&Entry> = ring
+            .entries
+            .iter()
+            .rev()
+            .filter(|e| query.outcome.is_none_or(|o| e.outcome == o))
+            .filter(|e| query.source.is_none_or(|s| e.source.kind() == s))
+            .filter(|e| query.since.is_none_or(|since| e.timestamp >= since))
+            .filter(|e| {
+                needle.as_deref().is_none_or(|needle| {
+                    e.title.to_lowercase().contains(needle)
+                        || e.message.to_lowercase().contains(needle)
+                })
+            })
+            .collect();
+
+        let offset = query.offset.unwrap_or(0);
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        Page {
+            total: matching.len(),
+            offset,
+            limit,
+            entries: matching
+                .into_iter()
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl Clone for History {
+    fn clone(&self) -> Self {
+        History {
+            inner: Arc::clone(&self.inner),
+            events: self.events.clone(),
+            metrics: self.metrics.clone(),
+            health: self.health.clone(),
+        }
+    }
+}
@@ -0,0 +1,784 @@
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Language {
+    En,
+    De,
+}
+
+impl Language {
+    pub(crate) fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "en" | "english" => Some(Language::En),
+            "de" | "german" | "deutsch" => Some(Language::De),
+            _ => None,
+        }
+    }
+
+    fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Language::from_tag(&value))
+            .unwrap_or(Language::En)
+    }
+}
+
+// 0 means "not chosen yet", in which case the locale environment decides.
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+pub(crate) fn set_language(language: Language) {
+    let value = match language {
+        Language::En => 1,
+        Language::De => 2,
+    };
+    LANGUAGE.store(value, Ordering::Relaxed);
+}
+
+pub(crate) fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::En,
+        2 => Language::De,
+        _ => Language::from_env(),
+    }
+}
+
+pub(crate) fn t(msg: Msg) -> &'static str {
+    msg.text(language())
+}
+
+pub(crate) fn tf(msg: Msg, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(t(msg).to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), &value.to_string())
+    })
+}
+
+macro_rules! tr {
+    ($msg:ident) => {
+        $crate::i18n::t($crate::i18n::Msg::$msg)
+    };
+    ($msg:ident, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::tf(
+            $crate::i18n::Msg::$msg,
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
+pub(crate) use tr;
+
+macro_rules! catalog {
+    ($($key:ident { en: $en:literal, de: $de:literal })*) => {
+        #[derive(Debug, Clone, Copy)]
+        pub(crate) enum Msg {
+            $($key,)*
+        }
+
+        impl Msg {
+            fn text(self, language: Language) -> &'static str {
+                match (self, language) {
+                    $(
+                        (Msg::$key, Language::En) => $en,
+                        (Msg::$key, Language::De) => $de,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+catalog! {
+    // Intervals
+    IntervalEmpty {
+        en: "Interval is empty",
+        de: "Intervall ist leer"
+    }
+    IntervalExpectedNumber {
+        en: "Expected a number at position {pos} of '{input}', found '{found}'",
+        de: "Zahl an Position {pos} von '{input}' erwartet, '{found}' gefunden"
+    }
+    IntervalMissingUnit {
+        en: "Missing time unit after '{number}' at position {pos} of '{input}'",
+        de: "Zeiteinheit nach '{number}' an Position {pos} von '{input}' fehlt"
+    }
+    IntervalUnknownUnit {
+        en: "Unknown time unit '{unit}' at position {pos} of '{input}' (use s, m, h, d or w)",
+        de: "Unbekannte Zeiteinheit '{unit}' an Position {pos} von '{input}' (erlaubt: s, m, h, d, w)"
+    }
+    IntervalIsoUnexpected {
+        en: "Unexpected '{found}' at position {pos} of ISO-8601 duration '{input}'",
+        de: "Unerwartetes '{found}' an Position {pos} der ISO-8601-Dauer '{input}'"
+    }
+    IntervalZero {
+        en: "Interval must be greater than 0",
+        de: "Intervall muss größer als 0 sein"
+    }
+    IntervalTooLong {
+        en: "Duration at position {pos} of '{input}' exceeds the maximum of {max}",
+        de: "Dauer an Position {pos} von '{input}' überschreitet das Maximum von {max}"
+    }
+    IntervalJitterTooLarge {
+        en: "Jitter {jitter} must be smaller than the interval {interval}",
+        de: "Streuung {jitter} muss kleiner als das Intervall {interval} sein"
+    }
+
+    // Startup and shutdown
+    HomeNotSet {
+        en: "Error: HOME environment variable is not set",
+        de: "Fehler: HOME-Umgebungsvariable ist nicht gesetzt"
+    }
+    CreatingDefaultFiles {
+        en: "Creating default configuration files {config} and {notifications}...",
+        de: "Erstelle Standardkonfigurationsdateien {config} und {notifications}..."
+    }
+    ConfigLoaded {
+        en: "Configuration file loaded: {path}",
+        de: "Konfigurationsdatei geladen: {path}"
+    }
+    NotificationsLoaded {
+        en: "Notifications file loaded: {path}",
+        de: "Benachrichtigungsdatei geladen: {path}"
+    }
+    UnknownLanguage {
+        en: "Unknown language '{language}', using the system locale",
+        de: "Unbekannte Sprache '{language}', verwende die Systemsprache"
+    }
+    InvalidListenAddress {
+        en: "Invalid listen_address '{address}': {error}",
+        de: "Ungültige listen_address '{address}': {error}"
+    }
+    InsecureBind {
+        en: "Webserver bound to non-localhost address {address} without API token. Consider setting an api_token in config.json for security.",
+        de: "Webserver lauscht ohne API-Token auf der nicht-lokalen Adresse {address}. Aus Sicherheitsgründen sollte in config.json ein api_token gesetzt werden."
+    }
+    PlaintextBind {
+        en: "Webserver bound to non-localhost address {address} without TLS; API tokens are sent in cleartext. Consider configuring tls in config.json.",
+        de: "Webserver lauscht ohne TLS auf der nicht-lokalen Adresse {address}; API-Token werden im Klartext übertragen. Es empfiehlt sich, tls in config.json zu konfigurieren."
+    }
+    BindFailed {
+        en: "Failed to listen on {address}: {error}",
+        de: "Lauschen auf {address} fehlgeschlagen: {error}"
+    }
+    WebserverStarted {
+        en: "Webserver listening on {address} ({scheme})",
+        de: "Webserver lauscht auf {address} ({scheme})"
+    }
+    ListenAddressInvalid {
+        en: "'{address}' is neither host:port nor unix:<path>",
+        de: "'{address}' ist weder host:port noch unix:<Pfad>"
+    }
+    SocketActivated {
+        en: "Using {count} socket(s) passed by systemd",
+        de: "Verwende {count} von systemd übergebene(n) Socket(s)"
+    }
+    SocketActivationFailed {
+        en: "Socket {fd} passed by systemd is unusable: {error}",
+        de: "Von systemd übergebener Socket {fd} ist unbrauchbar: {error}"
+    }
+    TlsCertInvalid {
+        en: "Failed to load TLS certificate '{path}': {error}",
+        de: "TLS-Zertifikat '{path}' konnte nicht geladen werden: {error}"
+    }
+    TlsKeyInvalid {
+        en: "Failed to load TLS private key '{path}': {error}",
+        de: "Privater TLS-Schlüssel '{path}' konnte nicht geladen werden: {error}"
+    }
+    TlsClientCaInvalid {
+        en: "Failed to load client CA bundle '{path}': {error}",
+        de: "Client-CA-Bundle '{path}' konnte nicht geladen werden: {error}"
+    }
+    AcceptFailed {
+        en: "Failed to accept connection on {address}: {error}",
+        de: "Verbindung auf {address} konnte nicht angenommen werden: {error}"
+    }
+    TlsHandshakeFailed {
+        en: "TLS handshake with {address} failed: {error}",
+        de: "TLS-Handshake mit {address} fehlgeschlagen: {error}"
+    }
+    ConnectionFailed {
+        en: "Connection from {address} failed: {error}",
+        de: "Verbindung von {address} fehlgeschlagen: {error}"
+    }
+    WebserverShutdown {
+        en: "Shutdown signal received, stopping webserver...",
+        de: "Signal zum Beenden empfangen, fahre Webserver herunter..."
+    }
+    WebserverDisabled {
+        en: "Webserver disabled. Running (Ctrl+C to exit)...",
+        de: "Webserver deaktiviert. Programm läuft (Ctrl+C zum Beenden)..."
+    }
+    ShutdownSignal {
+        en: "Shutdown signal received.",
+        de: "Signal zum Beenden empfangen."
+    }
+
+    // Configuration files
+    ConfigReadFailed {
+        en: "Failed to read {kind} file '{path}': {error}",
+        de: "{kind}-Datei '{path}' konnte nicht gelesen werden: {error}"
+    }
+    ConfigParseFailed {
+        en: "Failed to parse {kind} file '{path}': {error}",
+        de: "{kind}-Datei '{path}' konnte nicht geparst werden: {error}"
+    }
+    ConfigInvalid {
+        en: "Invalid {kind} file '{path}': {error}",
+        de: "Ungültige {kind}-Datei '{path}': {error}"
+    }
+    ConfigVersionTooNew {
+        en: "{kind} file '{path}' has version {version}, but this pushel only supports up to version {supported}",
+        de: "{kind}-Datei '{path}' hat Version {version}, diese pushel-Version unterstützt höchstens Version {supported}"
+    }
+    ConfigVersionInvalid {
+        en: "{kind} file '{path}' has an invalid version {version}",
+        de: "{kind}-Datei '{path}' hat eine ungültige Version {version}"
+    }
+    ConfigBackupFailed {
+        en: "Failed to back up '{path}': {error}",
+        de: "Sicherung von '{path}' fehlgeschlagen: {error}"
+    }
+    ConfigWriteFailed {
+        en: "Failed to write migrated '{path}': {error}",
+        de: "Migrierte Datei '{path}' konnte nicht geschrieben werden: {error}"
+    }
+    ConfigMigrated {
+        en: "Migrated {kind} file '{path}' from version {from} to {to} (backup: '{backup}')",
+        de: "{kind}-Datei '{path}' von Version {from} auf {to} migriert (Sicherung: '{backup}')"
+    }
+    ConfigMigrationPending {
+        en: "{kind} file '{path}' uses schema version {from}; it will be migrated to {to} on the next daemon start",
+        de: "{kind}-Datei '{path}' verwendet Schema-Version {from}; sie wird beim nächsten Daemon-Start auf {to} migriert"
+    }
+    ConfigDeprecatedKey {
+        en: "Deprecated key '{key}' in {kind} file '{path}': {hint}",
+        de: "Veralteter Schlüssel '{key}' in {kind}-Datei '{path}': {hint}"
+    }
+    ConfigUnknownKey {
+        en: "Unknown key '{key}' in {kind} file '{path}' is ignored",
+        de: "Unbekannter Schlüssel '{key}' in {kind}-Datei '{path}' wird ignoriert"
+    }
+    ConfigHintDefaultTitle {
+        en: "use 'defaults.title' instead",
+        de: "stattdessen 'defaults.title' verwenden"
+    }
+    ConfigWarning {
+        en: "Warning: {error}",
+        de: "Warnung: {error}"
+    }
+
+    // Idle detection and scheduling
+    IdleThreadStarted {
+        en: "Idle detection thread started",
+        de: "Idle-Erkennung gestartet"
+    }
+    IdleError {
+        en: "Failed to get idle time: {error}",
+        de: "Fehler beim Abrufen der Idle-Zeit: {error}"
+    }
+    IdlePanic {
+        en: "PANIC while getting idle time (probably an X11 error): {error}",
+        de: "PANIC beim Abrufen der Idle-Zeit (vermutlich X11-Fehler): {error}"
+    }
+    IdleErrorCount {
+        en: "{error} (error {count}/{max})",
+        de: "{error} (Fehler {count}/{max})"
+    }
+    IdleTooManyErrors {
+        en: "Too many consecutive errors. Pausing for {secs}s...",
+        de: "Zu viele aufeinanderfolgende Fehler. Pausiere für {secs}s..."
+    }
+    UserActive {
+        en: "User is active (idle: {secs}s)",
+        de: "Benutzer ist aktiv (idle: {secs}s)"
+    }
+    UserIdle {
+        en: "User is idle ({secs}s)",
+        de: "Benutzer ist idle ({secs}s)"
+    }
+    MotionStatusChanged {
+        en: "Motion status changed: {from} -> {to}",
+        de: "Bewegungsstatus geändert: {from} -> {to}"
+    }
+    MotionNotify {
+        en: "Motion detected within the last 15 minutes. Sending notification...",
+        de: "Bewegung in den letzten 15 Minuten erkannt. Sende Benachrichtigung..."
+    }
+    MotionSkip {
+        en: "No motion detected within the last 15 minutes. No notification sent.",
+        de: "Keine Bewegung in den letzten 15 Minuten erkannt. Keine Benachrichtigung gesendet."
+    }
+    ReminderDailyLimit {
+        en: "Daily limit reached for '{title}'. No notification sent.",
+        de: "Tageslimit für '{title}' erreicht. Keine Benachrichtigung gesendet."
+    }
+    ReminderFinished {
+        en: "Reminder '{title}' finished",
+        de: "Erinnerung '{title}' beendet"
+    }
+
+    // Delivery
+    NotificationSent {
+        en: "Notification sent: {title}",
+        de: "Benachrichtigung gesendet: {title}"
+    }
+    NotifySendFailed {
+        en: "notify-send failed with exit code: {code} (notification: {title})",
+        de: "notify-send fehlgeschlagen mit Exit-Code: {code} (Benachrichtigung: {title})"
+    }
+    NotifySendError {
+        en: "Error executing notify-send: {error} (notification: {title})",
+        de: "Fehler beim Ausführen von notify-send: {error} (Benachrichtigung: {title})"
+    }
+    NotifySendMissing {
+        en: "notify-send is not available: {error}",
+        de: "notify-send ist nicht verfügbar: {error}"
+    }
+
+    // Command-sourced reminders
+    CommandNotAllowed {
+        en: "Command '{program}' is not an absolute path listed in command_allowlist",
+        de: "Befehl '{program}' ist kein absoluter Pfad aus command_allowlist"
+    }
+    CommandSpawnFailed {
+        en: "Failed to run command '{program}': {error}",
+        de: "Befehl '{program}' konnte nicht ausgeführt werden: {error}"
+    }
+    CommandTimedOut {
+        en: "Command '{program}' timed out after {secs}s and was killed",
+        de: "Befehl '{program}' wurde nach {secs}s Zeitüberschreitung abgebrochen"
+    }
+    CommandSkipped {
+        en: "Command for '{title}' exited non-zero or printed nothing. No notification sent.",
+        de: "Befehl für '{title}' endete mit Fehler oder ohne Ausgabe. Keine Benachrichtigung gesendet."
+    }
+
+    // Watchers
+    WatchInvalidCondition {
+        en: "A watch needs exactly one of file_exists, file_changed, process_exited, http_failing or command_matches",
+        de: "Ein Watcher benötigt genau eine der Bedingungen file_exists, file_changed, process_exited, http_failing oder command_matches"
+    }
+    WatchInvalidPattern {
+        en: "Invalid pattern '{pattern}': {error}",
+        de: "Ungültiges Muster '{pattern}': {error}"
+    }
+    WatchStarted {
+        en: "Watching {target}",
+        de: "Überwache {target}"
+    }
+    WatchFired {
+        en: "Condition met for {target}. Notification sent.",
+        de: "Bedingung für {target} erfüllt. Benachrichtigung gesendet."
+    }
+    WatchCleared {
+        en: "Condition for {target} cleared",
+        de: "Bedingung für {target} nicht mehr erfüllt"
+    }
+    WatchCheckFailed {
+        en: "Checking {target} failed: {error}",
+        de: "Prüfung von {target} fehlgeschlagen: {error}"
+    }
+
+    // pushel run
+    RunSucceeded {
+        en: "✔ Finished: {command}",
+        de: "✔ Fertig: {command}"
+    }
+    RunFailed {
+        en: "✘ Failed: {command}",
+        de: "✘ Fehlgeschlagen: {command}"
+    }
+    RunSummary {
+        en: "Exit code {code} after {duration}",
+        de: "Exit-Code {code} nach {duration}"
+    }
+
+    // Client modes (pushel run, pushel send)
+    DaemonUnreachable {
+        en: "pushel daemon not reachable ({error}), sending notification directly",
+        de: "pushel-Daemon nicht erreichbar ({error}), sende Benachrichtigung direkt"
+    }
+    DaemonRejected {
+        en: "pushel daemon rejected the notification: {error}",
+        de: "pushel-Daemon hat die Benachrichtigung abgelehnt: {error}"
+    }
+    SendInvalidRecord {
+        en: "Line {line}: {error}",
+        de: "Zeile {line}: {error}"
+    }
+    SendReadFailed {
+        en: "Failed to read from stdin: {error}",
+        de: "Lesen von stdin fehlgeschlagen: {error}"
+    }
+    SendStdinRequired {
+        en: "--lines and --json read from stdin, pass `-` as the message",
+        de: "--lines und --json lesen von stdin, `-` als Nachricht angeben"
+    }
+    TokenCreated {
+        en: "API token \"{name}\" (shown only once, store it now):",
+        de: "API-Token \"{name}\" (wird nur einmal angezeigt, jetzt sichern):"
+    }
+    TokenConfigHint {
+        en: "Add this entry to {path} and restart the daemon:",
+        de: "Diesen Eintrag in {path} ergänzen und den Daemon neu starten:"
+    }
+
+    // Validation
+    ValidateEmpty {
+        en: "must not be empty",
+        de: "darf nicht leer sein"
+    }
+    ValidateTooLong {
+        en: "exceeds maximum length of {max}",
+        de: "überschreitet die maximale Länge von {max}"
+    }
+    ValidateTooShort {
+        en: "must be at least {min} characters long",
+        de: "muss mindestens {min} Zeichen lang sein"
+    }
+    ValidatePositive {
+        en: "must be greater than 0",
+        de: "muss größer als 0 sein"
+    }
+    ValidateConflict {
+        en: "cannot be combined with {other}",
+        de: "kann nicht mit {other} kombiniert werden"
+    }
+    ValidateTimerOnly {
+        en: "only applies to timed reminders, not to watchers",
+        de: "gilt nur für zeitgesteuerte Erinnerungen, nicht für Watcher"
+    }
+    ValidateIconPath {
+        en: "must be an icon name, path separators are not allowed",
+        de: "muss ein Icon-Name sein, Pfadtrenner sind nicht erlaubt"
+    }
+    ValidateTokenHash {
+        en: "must be `sha256:` followed by 64 hex digits, see `pushel token`",
+        de: "muss `sha256:` gefolgt von 64 Hex-Ziffern sein, siehe `pushel token`"
+    }
+    ValidatePriority {
+        en: "unknown priority '{value}'",
+        de: "unbekannte Priorität '{value}'"
+    }
+    ValidateUtf8 {
+        en: "must be UTF-8 text",
+        de: "muss UTF-8-Text sein"
+    }
+    ValidateEnvName {
+        en: "'{name}' cannot be set, it changes how programs are loaded",
+        de: "'{name}' kann nicht gesetzt werden, da es das Laden von Programmen verändert"
+    }
+    ValidateDuplicate {
+        en: "is used more than once",
+        de: "wird mehrfach verwendet"
+    }
+
+    // Coalescing and deduplication
+    NotificationsCoalesced {
+        en: "{count} notifications",
+        de: "{count} Benachrichtigungen"
+    }
+    NotificationsCoalescedLog {
+        en: "Merged {count} notifications into one",
+        de: "{count} Benachrichtigungen zusammengefasst"
+    }
+    NotificationDeduplicated {
+        en: "Duplicate notification for key '{key}' suppressed",
+        de: "Doppelte Benachrichtigung für Schlüssel '{key}' unterdrückt"
+    }
+
+    // Home Assistant
+    HaPushFailed {
+        en: "Failed to push status to Home Assistant: {error}",
+        de: "Status konnte nicht an Home Assistant übertragen werden: {error}"
+    }
+    HaPushSucceeded {
+        en: "Successfully pushed motion status to Home Assistant: {status} (attempt {attempt})",
+        de: "Bewegungsstatus an Home Assistant übertragen: {status} (Versuch {attempt})"
+    }
+    HaPushHttpError {
+        en: "Failed to push to Home Assistant. Status: {status} (attempt {attempt})",
+        de: "Übertragung an Home Assistant fehlgeschlagen. Status: {status} (Versuch {attempt})"
+    }
+    HaPushNetworkError {
+        en: "Network error pushing to Home Assistant: {error} (attempt {attempt})",
+        de: "Netzwerkfehler bei der Übertragung an Home Assistant: {error} (Versuch {attempt})"
+    }
+    HaNetworkError {
+        en: "Network error: {error}",
+        de: "Netzwerkfehler: {error}"
+    }
+    HaUnknownError {
+        en: "Unknown error",
+        de: "Unbekannter Fehler"
+    }
+    HaPushRetry {
+        en: "Retrying in {ms}ms...",
+        de: "Neuer Versuch in {ms}ms..."
+    }
+    HaPushGaveUp {
+        en: "Failed to push to Home Assistant after {attempts} attempts. Last error: {error}",
+        de: "Übertragung an Home Assistant nach {attempts} Versuchen fehlgeschlagen. Letzter Fehler: {error}"
+    }
+
+    // API
+    ApiRateLimitedLog {
+        en: "Rate limit exceeded for IP: {ip}",
+        de: "Rate-Limit überschritten für IP: {ip}"
+    }
+    ApiRateLimitedUnknownLog {
+        en: "Rate limit exceeded for clients without an address",
+        de: "Rate-Limit überschritten für Clients ohne Adresse"
+    }
+    ApiUnauthorizedLog {
+        en: "Unauthorized API request",
+        de: "Nicht autorisierte API-Anfrage"
+    }
+    ApiTokenRateLimitedLog {
+        en: "Rate limit exceeded for API token {token}",
+        de: "Rate-Limit überschritten für API-Token {token}"
+    }
+    ApiTokenExpiredLog {
+        en: "API request with expired token {token}",
+        de: "API-Anfrage mit abgelaufenem Token {token}"
+    }
+    ApiForbiddenLog {
+        en: "API token {token} lacks the {scope} scope",
+        de: "API-Token {token} fehlt der Scope {scope}"
+    }
+    ApiAuthorizedLog {
+        en: "API request authorized with token {token}",
+        de: "API-Anfrage mit Token {token} autorisiert"
+    }
+    ApiNotifyLog {
+        en: "Notification received via API token {token}",
+        de: "Benachrichtigung über API-Token {token} empfangen"
+    }
+    ApiSignatureStaleLog {
+        en: "Signed request from webhook sender {sender} outside the replay window",
+        de: "Signierte Anfrage von Webhook-Absender {sender} außerhalb des Replay-Fensters"
+    }
+    ApiSignatureReplayedLog {
+        en: "Replayed signature from webhook sender {sender}",
+        de: "Wiederholte Signatur von Webhook-Absender {sender}"
+    }
+    ApiRateLimited {
+        en: "Rate limit exceeded. Try again later.",
+        de: "Rate-Limit überschritten. Bitte später erneut versuchen."
+    }
+    ApiUnauthorized {
+        en: "Unauthorized. Provide a valid Bearer token.",
+        de: "Nicht autorisiert. Bitte ein gültiges Bearer-Token angeben."
+    }
+    ApiTokenExpired {
+        en: "The API token has expired.",
+        de: "Das API-Token ist abgelaufen."
+    }
+    ApiForbidden {
+        en: "The API token lacks the {scope} scope.",
+        de: "Dem API-Token fehlt der Scope {scope}."
+    }
+    ApiSignatureStale {
+        en: "The request timestamp is outside the {window} replay window.",
+        de: "Der Zeitstempel der Anfrage liegt außerhalb des Replay-Fensters von {window}."
+    }
+    ApiSignatureReplayed {
+        en: "This signed request has already been received.",
+        de: "Diese signierte Anfrage wurde bereits empfangen."
+    }
+    ApiNotificationSent {
+        en: "Notification sent",
+        de: "Benachrichtigung gesendet"
+    }
+    ApiDuplicateSuppressed {
+        en: "Duplicate suppressed",
+        de: "Duplikat unterdrückt"
+    }
+    ApiNotFound {
+        en: "No such endpoint",
+        de: "Unbekannter Endpunkt"
+    }
+    ApiMethodNotAllowed {
+        en: "Method not allowed for this endpoint",
+        de: "Methode für diesen Endpunkt nicht erlaubt"
+    }
+    ApiPayloadTooLarge {
+        en: "Request body exceeds {max} bytes",
+        de: "Anfrageinhalt überschreitet {max} Bytes"
+    }
+    ApiLengthRequired {
+        en: "Content-Length header is required",
+        de: "Content-Length-Header ist erforderlich"
+    }
+    ApiUnsupportedMediaType {
+        en: "Content-Type must be application/json",
+        de: "Content-Type muss application/json sein"
+    }
+    ApiInvalidHeader {
+        en: "Missing or invalid header: {header}",
+        de: "Fehlender oder ungültiger Header: {header}"
+    }
+    ApiInvalidQuery {
+        en: "Invalid query parameters: {error}",
+        de: "Ungültige Abfrageparameter: {error}"
+    }
+    ApiInternalError {
+        en: "Internal server error",
+        de: "Interner Serverfehler"
+    }
+    ApiUnhandledRejection {
+        en: "Unhandled API rejection: {error}",
+        de: "Unbehandelte API-Ablehnung: {error}"
+    }
+
+    // Compatibility endpoints
+    NtfyTriggered {
+        en: "triggered",
+        de: "ausgelöst"
+    }
+
+    // TUI
+    TuiTitle {
+        en: "Pushel Notification Manager",
+        de: "Pushel Benachrichtigungsverwaltung"
+    }
+    TuiError {
+        en: "TUI error: {error}",
+        de: "TUI-Fehler: {error}"
+    }
+    TuiNotifications {
+        en: "Notifications",
+        de: "Benachrichtigungen"
+    }
+    TuiDetails {
+        en: "Details: #{index}",
+        de: "Details: #{index}"
+    }
+    TuiNoSelection {
+        en: "No notification selected",
+        de: "Keine Benachrichtigung ausgewählt"
+    }
+    TuiTitleLabel {
+        en: "Title",
+        de: "Titel"
+    }
+    TuiMessageLabel {
+        en: "Message",
+        de: "Nachricht"
+    }
+    TuiIntervalLabel {
+        en: "Interval",
+        de: "Intervall"
+    }
+    TuiUrgencyLabel {
+        en: "Urgency",
+        de: "Dringlichkeit"
+    }
+    TuiExpireTimeLabel {
+        en: "Expire Time",
+        de: "Ablaufzeit"
+    }
+    TuiAppNameLabel {
+        en: "App Name",
+        de: "App-Name"
+    }
+    TuiIconLabel {
+        en: "Icon",
+        de: "Icon"
+    }
+    TuiCategoryLabel {
+        en: "Category",
+        de: "Kategorie"
+    }
+    TuiTransientLabel {
+        en: "Transient",
+        de: "Flüchtig"
+    }
+    TuiHelpNavigate {
+        en: "[↑↓/jk] Navigate",
+        de: "[↑↓/jk] Navigieren"
+    }
+    TuiHelpEdit {
+        en: "[e] Edit in $EDITOR",
+        de: "[e] In $EDITOR bearbeiten"
+    }
+    TuiHelpReload {
+        en: "[r] Reload",
+        de: "[r] Neu laden"
+    }
+    TuiHelpQuit {
+        en: "[q/Esc] Quit",
+        de: "[q/Esc] Beenden"
+    }
+    TuiHelpHistory {
+        en: "[h] History",
+        de: "[h] Verlauf"
+    }
+    TuiHelpNotifications {
+        en: "[h] Notifications",
+        de: "[h] Benachrichtigungen"
+    }
+    TuiReloaded {
+        en: "Notifications reloaded.",
+        de: "Benachrichtigungen neu geladen."
+    }
+    TuiHistory {
+        en: "History ({total} entries)",
+        de: "Verlauf ({total} Einträge)"
+    }
+    TuiHistoryReloaded {
+        en: "History reloaded.",
+        de: "Verlauf neu geladen."
+    }
+    TuiHistoryUnavailable {
+        en: "History unavailable: {error}",
+        de: "Verlauf nicht verfügbar: {error}"
+    }
+    TuiHistoryNeedsWebserver {
+        en: "the history is served by the daemon's web server, which is disabled",
+        de: "der Verlauf wird vom Webserver des Daemons bereitgestellt, der deaktiviert ist"
+    }
+    TuiNoHistoryEntry {
+        en: "No history entry selected",
+        de: "Kein Verlaufseintrag ausgewählt"
+    }
+    TuiTimeLabel {
+        en: "Time",
+        de: "Zeit"
+    }
+    TuiSourceLabel {
+        en: "Source",
+        de: "Quelle"
+    }
+    TuiOutcomeLabel {
+        en: "Outcome",
+        de: "Ergebnis"
+    }
+    TuiReasonLabel {
+        en: "Reason",
+        de: "Grund"
+    }
+    TuiEditorNotAllowed {
+        en: "Editor '{editor}' is not in the safe list. Allowed: {allowed}",
+        de: "Editor '{editor}' ist nicht in der Liste sicherer Editoren. Erlaubt: {allowed}"
+    }
+    TuiEditorClosed {
+        en: "Editor '{editor}' closed. Notifications reloaded.",
+        de: "Editor '{editor}' geschlossen. Benachrichtigungen neu geladen."
+    }
+    TuiEditorExitCode {
+        en: "Editor '{editor}' exited with code {code}.",
+        de: "Editor '{editor}' wurde mit Code {code} beendet."
+    }
+    TuiEditorLaunchFailed {
+        en: "Failed to launch editor '{editor}': {error}",
+        de: "Editor '{editor}' konnte nicht gestartet werden: {error}"
+    }
+}
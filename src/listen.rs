@@ -0,0 +1,282 @@
+use crate::i18n::tr;
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto,
+};
+use serde::Deserialize;
+use std::{
+    convert::Infallible,
+    env, fmt, fs,
+    future::Future,
+    io,
+    net::SocketAddr,
+    os::unix::{
+        fs::FileTypeExt,
+        io::{FromRawFd, IntoRawFd, RawFd},
+    },
+    path::PathBuf,
+    process,
+    str::FromStr,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, UnixListener},
+};
+use tokio_rustls::TlsAcceptor;
+use tower_service::Service;
+use tracing::{debug, warn};
+use warp::{
+    http::Request,
+    hyper::{body::Incoming, service::service_fn},
+    reply::Response,
+};
+
+// First descriptor passed by systemd, see sd_listen_fds(3).
+const SD_LISTEN_FDS_START: RawFd = 3;
+// Clients that stall the handshake would otherwise hold their connection open
+// forever.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// An entry of `listen`: `127.0.0.1:3030`, `[::1]:3030` or
+// `unix:/run/user/1000/pushel.sock`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub(crate) enum ListenAddress {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl FromStr for ListenAddress {
+    type Err = String;
+
+    fn from_str(address: &str) -> Result<Self, String> {
+        match address.strip_prefix("unix:") {
+            Some("") => Err(tr!(ListenAddressInvalid, address = address)),
+            Some(path) => Ok(ListenAddress::Unix(PathBuf::from(path))),
+            None => address
+                .parse()
+                .map(ListenAddress::Tcp)
+                .map_err(|_| tr!(ListenAddressInvalid, address = address)),
+        }
+    }
+}
+
+impl TryFrom<String> for ListenAddress {
+    type Error = String;
+
+    fn try_from(address: String) -> Result<Self, String> {
+        address.parse()
+    }
+}
+
+impl fmt::Display for ListenAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListenAddress::Tcp(address) => write!(f, "{address}"),
+            ListenAddress::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+// Client address of a connection accepted by `serve`. warp only records it
+// for connections it accepts itself, see `api::remote`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PeerAddr(pub(crate) SocketAddr);
+
+// Descriptors passed by systemd socket activation, if any. Clears the
+// `LISTEN_*` variables so that commands started by pushel don't take the
+// sockets for theirs; as that changes the environment, it must run before
+// any other thread is started.
+pub(crate) fn systemd_fds() -> Vec<RawFd> {
+    let pid = env::var("LISTEN_PID").ok().and_then(|p| p.parse().ok());
+    let count = env::var("LISTEN_FDS")
+        .ok()
+        .and_then(|n| n.parse::<RawFd>().ok())
+        .unwrap_or(0);
+    for name in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        env::remove_var(name);
+    }
+    if pid != Some(process::id()) {
+        return Vec::new();
+    }
+    (SD_LISTEN_FDS_START..SD_LISTEN_FDS_START + count).collect()
+}
+
+pub(crate) enum Listener {
+    Tcp(TcpListener),
+    Unix {
+        listener: UnixListener,
+        path: PathBuf,
+        // Sockets passed by systemd belong to the socket unit.
+        owned: bool,
+    },
+}
+
+impl Listener {
+    pub(crate) async fn bind(address: &ListenAddress) -> Result<Self, String> {
+        let failed = |e: io::Error| tr!(BindFailed, address = address, error = e);
+        match address {
+            ListenAddress::Tcp(socket_addr) => TcpListener::bind(socket_addr)
+                .await
+                .map(Listener::Tcp)
+                .map_err(failed),
+            ListenAddress::Unix(path) => {
+                // A socket file left behind by a crash would make the bind
+                // fail, but one that still answers belongs to a running daemon.
+                let stale = fs::metadata(path).is_ok_and(|m| m.file_type().is_socket())
+                    && std::os::unix::net::UnixStream::connect(path).is_err();
+                if stale {
+                    fs::remove_file(path).map_err(failed)?;
+                }
+                UnixListener::bind(path)
+                    .map(|listener| Listener::Unix {
+                        listener,
+                        path: path.clone(),
+                        owned: true,
+                    })
+                    .map_err(failed)
+            }
+        }
+    }
+
+    // Takes over the descriptors returned by `systemd_fds`.
+    pub(crate) fn from_systemd(fds: Vec<RawFd>) -> Result<Vec<Self>, String> {
+        fds.into_iter()
+            .map(|fd| {
+                Listener::from_fd(fd).map_err(|e| tr!(SocketActivationFailed, fd = fd, error = e))
+            })
+            .collect()
+    }
+
+    fn from_fd(fd: RawFd) -> io::Result<Self> {
+        // SAFETY: systemd passes these descriptors to this process only, and
+        // each is taken over exactly once.
+        let tcp = unsafe { std::net::TcpListener::from_raw_fd(fd) };
+        // The duplicate is close-on-exec, unlike what systemd passes in, so
+        // commands started by pushel don't inherit the socket.
+        if tcp.local_addr().is_ok() {
+            let tcp = tcp.try_clone()?;
+            tcp.set_nonblocking(true)?;
+            return TcpListener::from_std(tcp).map(Listener::Tcp);
+        }
+        // SAFETY: as above; `into_raw_fd` gives up the TCP wrapper's ownership.
+        let unix = unsafe { std::os::unix::net::UnixListener::from_raw_fd(tcp.into_raw_fd()) };
+        let path = unix
+            .local_addr()?
+            .as_pathname()
+            .map(PathBuf::from)
+            .unwrap_or_default();
+        let unix = unix.try_clone()?;
+        unix.set_nonblocking(true)?;
+        Ok(Listener::Unix {
+            listener: UnixListener::from_std(unix)?,
+            path,
+            owned: false,
+        })
+    }
+
+    // TCP address, for the warnings about exposed listeners.
+    pub(crate) fn tcp_address(&self) -> Option<SocketAddr> {
+        match self {
+            Listener::Tcp(listener) => listener.local_addr().ok(),
+            Listener::Unix { .. } => None,
+        }
+    }
+
+    async fn accept(&self) -> io::Result<(Box<dyn Io>, Option<SocketAddr>)> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, address) = listener.accept().await?;
+                Ok((Box::new(stream), Some(address)))
+            }
+            Listener::Unix { listener, .. } => {
+                let (stream, _) = listener.accept().await?;
+                Ok((Box::new(stream), None))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Listener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Listener::Tcp(listener) => match listener.local_addr() {
+                Ok(address) => write!(f, "{address}"),
+                Err(_) => f.write_str("tcp"),
+            },
+            Listener::Unix { path, .. } => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+trait Io: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Io for T {}
+
+// warp can only serve a single TCP address and can't terminate TLS, so
+// connections are accepted here and handed to the same service warp would run.
+pub(crate) async fn serve<S>(
+    listener: Listener,
+    tls: Option<TlsAcceptor>,
+    service: S,
+    shutdown: impl Future<Output = ()>,
+) where
+    S: Service<Request<Incoming>, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    tokio::pin!(shutdown);
+    loop {
+        let (stream, address) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    // Usually out of file descriptors; don't spin on it.
+                    debug!("{}", tr!(AcceptFailed, address = listener, error = e));
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            },
+            () = &mut shutdown => break,
+        };
+        let peer = address.map_or_else(|| listener.to_string(), |a| a.to_string());
+        let tls = tls.clone();
+        let service = service.clone();
+        tokio::spawn(async move {
+            let stream: Box<dyn Io> = match tls {
+                Some(acceptor) => {
+                    match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream))
+                        .await
+                        .map_err(io::Error::from)
+                        .and_then(|accepted| accepted)
+                    {
+                        Ok(stream) => Box::new(stream),
+                        Err(e) => {
+                            warn!("{}", tr!(TlsHandshakeFailed, address = peer, error = e));
+                            return;
+                        }
+                    }
+                }
+                None => stream,
+            };
+            let service = service_fn(move |mut request: Request<Incoming>| {
+                if let Some(address) = address {
+                    request.extensions_mut().insert(PeerAddr(address));
+                }
+                service.clone().call(request)
+            });
+            if let Err(e) = auto::Builder::new(TokioExecutor::new())
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                debug!("{}", tr!(ConnectionFailed, address = peer, error = e));
+            }
+        });
+    }
+    if let Listener::Unix {
+        path, owned: true, ..
+    } = &listener
+    {
+        let _ = fs::remove_file(path);
+    }
+}
@@ -1,6 +1,38 @@
+mod api;
+mod auth;
+mod client;
+mod command;
+mod compat;
+mod config;
+mod dispatch;
+mod events;
+mod health;
+mod history;
+mod i18n;
+mod listen;
+mod metrics;
+mod model;
+mod ratelimit;
+mod reminder;
+mod run;
+mod send;
+mod template;
+mod tls;
 mod tui;
-
-use clap::Parser;
+mod validate;
+mod watch;
+
+use api::ApiError;
+use command::CommandSource;
+use config::{AppConfig, NotificationDefaults};
+use dispatch::Dispatcher;
+use i18n::{tr, Language};
+use model::{Interval, Notification, Urgency};
+use ratelimit::{Client, RateLimit, RateLimiter};
+use watch::WatchConfig;
+
+use clap::{CommandFactory, Parser, Subcommand};
+use rand::seq::IndexedRandom;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
@@ -8,84 +40,248 @@ use std::{
     fs::File,
     io::Write,
     net::IpAddr,
-    path::PathBuf,
+    os::unix::io::RawFd,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, OnceLock},
     thread,
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use tracing::{debug, error, info, warn};
 use user_idle::UserIdle;
-use warp::Filter;
+use warp::{Filter, Reply};
 
 #[derive(Parser)]
 #[command(name = "pushel", about = "Desktop notification reminder")]
 struct Cli {
     #[arg(short, long, help = "Launch TUI notification manager")]
     tui: bool,
+    #[arg(long, env = "PUSHEL_CONFIG_DIR", help = "Configuration directory")]
+    config_dir: Option<PathBuf>,
+    #[arg(short, long, env = "PUSHEL_CONFIG", help = "Path to the config file")]
+    config: Option<PathBuf>,
+    #[arg(
+        short,
+        long,
+        env = "PUSHEL_NOTIFICATIONS",
+        help = "Path to the notifications file"
+    )]
+    notifications: Option<PathBuf>,
+    #[arg(long, env = "PUSHEL_LISTEN_ADDRESS", help = "Override listen_address")]
+    listen_address: Option<String>,
+    #[arg(short, long, env = "PUSHEL_PORT", help = "Override port")]
+    port: Option<u16>,
+    #[arg(
+        long = "listen",
+        env = "PUSHEL_LISTEN",
+        value_delimiter = ',',
+        help = "Override listen, e.g. 127.0.0.1:3030,unix:/run/pushel.sock"
+    )]
+    listen: Vec<listen::ListenAddress>,
+    #[arg(
+        long,
+        env = "PUSHEL_API_TOKEN",
+        hide_env_values = true,
+        help = "Override api_token"
+    )]
+    api_token: Option<String>,
+    #[arg(
+        long,
+        env = "PUSHEL_LOG_FORMAT",
+        value_parser = ["pretty", "json"],
+        help = "Override log_format"
+    )]
+    log_format: Option<String>,
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    #[command(about = "Run a command and send a notification when it finishes")]
+    Run {
+        #[arg(long, help = "Notification title (default: derived from the command)")]
+        title: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value_t = 10,
+            help = "Number of trailing output lines to include"
+        )]
+        lines: usize,
+        #[arg(
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            help = "Command to run, e.g. `pushel run -- make -j8`"
+        )]
+        command: Vec<String>,
+    },
+    #[command(about = "Send a notification, e.g. `journalctl -f | pushel send --lines -`")]
+    Send {
+        #[arg(long, help = "Notification title")]
+        title: Option<String>,
+        #[arg(short, long, value_enum, help = "Urgency level")]
+        urgency: Option<Urgency>,
+        #[arg(long, help = "Suppress repeats with the same key (see dedup_ttl)")]
+        dedup_key: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "json",
+            help = "Send one notification per line of stdin"
+        )]
+        lines: bool,
+        #[arg(
+            long,
+            help = "Read one JSON notification per line of stdin (same fields as the API)"
+        )]
+        json: bool,
+        #[arg(help = "Message text, or `-` to read from stdin")]
+        message: String,
+    },
+    #[command(about = "Create an API token and print its config entry")]
+    Token {
+        #[arg(help = "Name shown in logs and history, e.g. `ci`")]
+        name: String,
+        #[arg(
+            short,
+            long = "scope",
+            value_enum,
+            required = true,
+            help = "Allowed scope, may be repeated"
+        )]
+        scopes: Vec<auth::Scope>,
+        #[arg(long, help = "Expiry as RFC 3339, e.g. 2027-01-01T00:00:00Z")]
+        expires_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+        #[arg(long, help = "Requests per minute for this token")]
+        rate_limit_rpm: Option<u32>,
+    },
 }
 
-const VALID_URGENCIES: &[&str] = &["low", "normal", "critical"];
 const MAX_FIELD_LENGTH: usize = 1024;
 const MAX_MESSAGE_LENGTH: usize = 4096;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub(crate) struct NotificationConfig {
+    // Identifies the reminder in the history, metrics and events; filled in
+    // with its index in the file when not configured.
+    name: Option<String>,
     title: Option<String>,
+    #[serde(default)]
     message: String,
-    interval: String,
-    urgency: Option<String>,
+    #[serde(default)]
+    messages: Vec<String>,
+    interval: Interval,
+    initial_delay: Option<Interval>,
+    #[serde(default)]
+    fire_on_start: bool,
+    max_occurrences: Option<u32>,
+    max_per_day: Option<u32>,
+    stop_after: Option<Interval>,
+    urgency: Option<Urgency>,
     expire_time: Option<u32>,
     app_name: Option<String>,
     icon: Option<String>,
     category: Option<String>,
     transient: Option<bool>,
+    command: Option<CommandSource>,
+    watch: Option<WatchConfig>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 struct AdhocNotification {
     title: Option<String>,
     message: String,
-    urgency: Option<String>,
+    urgency: Option<Urgency>,
     expire_time: Option<u32>,
     app_name: Option<String>,
     icon: Option<String>,
     category: Option<String>,
     transient: Option<bool>,
+    dedup_key: Option<String>,
+    #[serde(default)]
+    variables: HashMap<String, String>,
 }
 
-impl From<AdhocNotification> for NotificationConfig {
-    fn from(notif: AdhocNotification) -> Self {
-        NotificationConfig {
-            title: notif.title,
-            message: notif.message,
-            interval: String::new(),
-            urgency: notif.urgency,
-            expire_time: notif.expire_time,
-            app_name: notif.app_name,
-            icon: notif.icon,
-            category: notif.category,
-            transient: notif.transient,
+impl NotificationConfig {
+    pub(crate) fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or_default()
+    }
+
+    pub(crate) fn with_defaults(mut self, defaults: &NotificationDefaults) -> Self {
+        self.title = self.title.or_else(|| Some(defaults.title.clone()));
+        self.urgency = self.urgency.or(defaults.urgency);
+        self.expire_time = self.expire_time.or(defaults.expire_time);
+        self.app_name = self.app_name.or_else(|| defaults.app_name.clone());
+        self.icon = self.icon.or_else(|| defaults.icon.clone());
+        self.category = self.category.or_else(|| defaults.category.clone());
+        self.transient = self.transient.or(defaults.transient);
+        self
+    }
+
+    fn render(&self, vars: &template::Vars) -> Notification {
+        // Command-sourced reminders without their own text show the output as is.
+        let message = match self.messages.choose(&mut rand::rng()) {
+            Some(message) => message.as_str(),
+            None if self.message.is_empty() => "{output}",
+            None => self.message.as_str(),
+        };
+        let title = self.title.as_deref().unwrap_or(config::DEFAULT_TITLE);
+        Notification {
+            title: template::render(title, vars),
+            message: template::render(message, vars),
+            urgency: self.urgency,
+            expire_time: self.expire_time,
+            app_name: self.app_name.clone(),
+            icon: self.icon.clone(),
+            category: self.category.clone(),
+            transient: self.transient.unwrap_or(false),
+            replaces_id: None,
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct AppConfig {
-    listen_address: String,
-    port: u16,
-    webserver_enabled: bool,
-    log_format: String,
-    #[serde(default)]
-    api_token: Option<String>,
-    #[serde(default = "default_rate_limit_rpm")]
-    rate_limit_rpm: u32,
-    homeassistant_url: Option<String>,
-    homeassistant_api_key: Option<String>,
+impl AdhocNotification {
+    // Caller-supplied variables take precedence over the built-in ones.
+    fn render(self, defaults: &NotificationDefaults, mut vars: template::Vars) -> Notification {
+        vars.extend(self.variables);
+        let title = self.title.as_deref().unwrap_or(&defaults.title);
+        Notification {
+            title: template::render(title, &vars),
+            message: template::render(&self.message, &vars),
+            urgency: self.urgency.or(defaults.urgency),
+            expire_time: self.expire_time.or(defaults.expire_time),
+            app_name: self.app_name.or_else(|| defaults.app_name.clone()),
+            icon: self.icon.or_else(|| defaults.icon.clone()),
+            category: self.category.or_else(|| defaults.category.clone()),
+            transient: self.transient.or(defaults.transient).unwrap_or(false),
+            replaces_id: None,
+        }
+    }
 }
 
-fn default_rate_limit_rpm() -> u32 {
-    60
+impl AppConfig {
+    fn apply_overrides(&mut self, cli: &Cli) {
+        if let Some(listen_address) = &cli.listen_address {
+            self.listen_address = listen_address.clone();
+        }
+        if let Some(port) = cli.port {
+            self.port = port;
+        }
+        // `listen` takes precedence, so an override of the single address
+        // has to clear it to have any effect.
+        if !cli.listen.is_empty() {
+            self.listen = cli.listen.clone();
+        } else if cli.listen_address.is_some() || cli.port.is_some() {
+            self.listen.clear();
+        }
+        if let Some(api_token) = &cli.api_token {
+            self.api_token = Some(api_token.clone());
+        }
+        if let Some(log_format) = &cli.log_format {
+            self.log_format = log_format.clone();
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize)]
@@ -103,25 +299,64 @@ impl MotionStatus {
     }
 }
 
+// An idle gap longer than this ends the current activity session.
+const ACTIVE_SESSION_GAP: Duration = Duration::from_secs(5 * 60);
+
 struct MotionTracker {
     last_motion: Arc<Mutex<Option<Instant>>>,
+    active_since: Arc<Mutex<Option<Instant>>>,
     current_status: Arc<Mutex<MotionStatus>>,
     runtime_handle: tokio::runtime::Handle,
+    events: events::Events,
+    metrics: metrics::Metrics,
+    health: health::Health,
 }
 
 impl MotionTracker {
-    fn new(runtime_handle: tokio::runtime::Handle) -> Self {
+    fn new(
+        runtime_handle: tokio::runtime::Handle,
+        events: events::Events,
+        metrics: metrics::Metrics,
+        health: health::Health,
+    ) -> Self {
         MotionTracker {
             last_motion: Arc::new(Mutex::new(None)),
+            active_since: Arc::new(Mutex::new(None)),
             current_status: Arc::new(Mutex::new(MotionStatus::Inactive)),
             runtime_handle,
+            events,
+            metrics,
+            health,
         }
     }
 
     fn update_motion(&self) {
-        if let Ok(mut last_motion) = self.last_motion.lock() {
-            *last_motion = Some(Instant::now());
+        let now = Instant::now();
+        if let (Ok(mut last_motion), Ok(mut active_since)) =
+            (self.last_motion.lock(), self.active_since.lock())
+        {
+            let session_ended = last_motion
+                .map(|t| now.duration_since(t) > ACTIVE_SESSION_GAP)
+                .unwrap_or(true);
+            if session_ended || active_since.is_none() {
+                *active_since = Some(now);
+            }
+            *last_motion = Some(now);
+        }
+    }
+
+    fn active_minutes(&self) -> u64 {
+        let now = Instant::now();
+        if let (Ok(last_motion), Ok(active_since)) =
+            (self.last_motion.lock(), self.active_since.lock())
+        {
+            if let (Some(last), Some(since)) = (*last_motion, *active_since) {
+                if now.duration_since(last) <= ACTIVE_SESSION_GAP {
+                    return now.duration_since(since).as_secs() / 60;
+                }
+            }
         }
+        0
     }
 
     fn should_notify(&self) -> bool {
@@ -144,10 +379,18 @@ impl MotionTracker {
             if let Ok(mut current_status) = self.current_status.lock() {
                 if *current_status != new_status {
                     info!(
-                        "Motion status changed: {:?} -> {:?}",
-                        *current_status, new_status
+                        "{}",
+                        tr!(
+                            MotionStatusChanged,
+                            from = current_status.as_str(),
+                            to = new_status.as_str(),
+                        )
                     );
                     *current_status = new_status;
+                    self.events.publish(events::Event::Motion {
+                        status: new_status.as_str().to_string(),
+                        timestamp: chrono::Local::now(),
+                    });
                     ha_url.is_some() && ha_api_key.is_some()
                 } else {
                     false
@@ -160,9 +403,16 @@ impl MotionTracker {
         if should_push {
             let url = ha_url.unwrap().to_string();
             let api_key = ha_api_key.unwrap().to_string();
+            let metrics = self.metrics.clone();
+            let health = self.health.clone();
             self.runtime_handle.spawn(async move {
-                if let Err(e) = push_to_homeassistant(&url, &api_key, new_status).await {
-                    error!("Failed to push status to Home Assistant: {}", e);
+                match push_to_homeassistant(&url, &api_key, new_status, &metrics).await {
+                    Ok(()) => health.ok(health::Component::HomeAssistant),
+                    Err(e) => {
+                        metrics.homeassistant_failure();
+                        error!("{}", tr!(HaPushFailed, error = e));
+                        health.degraded(health::Component::HomeAssistant, e);
+                    }
                 }
             });
         }
@@ -173,57 +423,187 @@ impl Clone for MotionTracker {
     fn clone(&self) -> Self {
         MotionTracker {
             last_motion: Arc::clone(&self.last_motion),
+            active_since: Arc::clone(&self.active_since),
             current_status: Arc::clone(&self.current_status),
             runtime_handle: self.runtime_handle.clone(),
+            events: self.events.clone(),
+            metrics: self.metrics.clone(),
+            health: self.health.clone(),
         }
     }
 }
 
-struct RateLimiter {
-    inner: Arc<Mutex<HashMap<IpAddr, (Instant, u32)>>>,
-    max_requests: u32,
-    window: Duration,
+// Authorization, validation and dispatch shared by `/api/v1/notify` and the
+// compatibility routes.
+#[derive(Clone)]
+struct NotifyEndpoint {
+    rate_limiter: RateLimiter,
+    tokens: auth::Tokens,
+    dispatcher: Dispatcher,
+    defaults: NotificationDefaults,
+    motion_tracker: MotionTracker,
 }
 
-impl RateLimiter {
-    fn new(rpm: u32) -> Self {
-        RateLimiter {
-            inner: Arc::new(Mutex::new(HashMap::new())),
-            max_requests: rpm,
-            window: Duration::from_secs(60),
+impl NotifyEndpoint {
+    // `reply` builds the success response in the caller's protocol.
+    fn submit(
+        &self,
+        remote: Option<IpAddr>,
+        authenticated: Result<Option<auth::Identity>, auth::AuthError>,
+        notification: Result<AdhocNotification, validate::ValidationErrors>,
+        reply: impl FnOnce(&AdhocNotification, bool) -> warp::reply::Response,
+    ) -> warp::reply::Response {
+        let access = match authorize(
+            &self.rate_limiter,
+            &self.tokens,
+            auth::Scope::Notify,
+            remote,
+            authenticated,
+        ) {
+            Ok(access) => access,
+            Err(error) => return error.into_response(),
+        };
+        let notification = match notification {
+            Ok(notification) => notification,
+            Err(errors) => return access.reply(ApiError::validation(errors)),
+        };
+        if let Some(identity) = &access.identity {
+            if self.tokens.record_signature(identity).is_err() {
+                warn!("{}", tr!(ApiSignatureReplayedLog, sender = identity.name));
+                return access.reply(ApiError::replayed());
+            }
         }
+        let vars = template::builtin_vars(self.motion_tracker.active_minutes());
+        let dedup_key = notification.dedup_key.clone();
+        let token = access
+            .identity
+            .as_ref()
+            .map(|identity| identity.name.clone());
+        if let Some(token) = &token {
+            info!("{}", tr!(ApiNotifyLog, token = token));
+        }
+        let source = history::Source::Api {
+            address: remote,
+            token,
+        };
+        let rendered = notification.clone().render(&self.defaults, vars);
+        // notify-send blocks, and this runs inside a warp handler.
+        let delivered = tokio::task::block_in_place(|| {
+            self.dispatcher
+                .submit(rendered, source, dedup_key.as_deref())
+        });
+        access.reply(reply(&notification, delivered))
     }
+}
 
-    fn check(&self, ip: IpAddr) -> bool {
-        let mut map = self.inner.lock().expect("rate limiter mutex poisoned");
-        let now = Instant::now();
+// A request that passed `check_access`.
+struct Access {
+    // `None` when no tokens are configured.
+    identity: Option<auth::Identity>,
+    rate_limit: RateLimit,
+}
 
-        map.retain(|_, (since, _)| now.duration_since(*since) <= self.window);
+impl Access {
+    fn reply(&self, reply: impl Reply) -> warp::reply::Response {
+        let mut response = reply.into_response();
+        self.rate_limit.apply(&mut response);
+        response
+    }
+}
 
-        let entry = map.entry(ip).or_insert((now, 0));
-        if now.duration_since(entry.0) > self.window {
-            entry.0 = now;
-            entry.1 = 0;
+// Rate limit, bearer token and scope check shared by all API routes.
+fn check_access(
+    rate_limiter: &RateLimiter,
+    tokens: &auth::Tokens,
+    scope: auth::Scope,
+    remote: Option<IpAddr>,
+    auth_header: Option<&str>,
+) -> Result<Access, ApiError> {
+    let authenticated = tokens.authenticate(auth_header);
+    authorize(rate_limiter, tokens, scope, remote, authenticated)
+}
+
+// Rate limiting and scope check for a bearer token or a webhook signature.
+fn authorize(
+    rate_limiter: &RateLimiter,
+    tokens: &auth::Tokens,
+    scope: auth::Scope,
+    remote: Option<IpAddr>,
+    authenticated: Result<Option<auth::Identity>, auth::AuthError>,
+) -> Result<Access, ApiError> {
+    // Failed attempts count against the address, so guessing is throttled too.
+    let (client, rpm) = match &authenticated {
+        Ok(Some(auth::Identity {
+            name,
+            rate_limit_rpm: Some(rpm),
+            ..
+        })) => (Client::Token(name.clone()), *rpm),
+        _ => (
+            remote.map_or(Client::Unknown, Client::Address),
+            rate_limiter.max_requests,
+        ),
+    };
+    let rate_limit = rate_limiter.check(client.clone(), rpm);
+    if !rate_limit.allowed {
+        match client {
+            Client::Token(name) => warn!("{}", tr!(ApiTokenRateLimitedLog, token = name)),
+            Client::Address(ip) => warn!("{}", tr!(ApiRateLimitedLog, ip = ip)),
+            Client::Unknown => warn!("{}", tr!(ApiRateLimitedUnknownLog)),
         }
-        entry.1 += 1;
-        entry.1 <= self.max_requests
+        return Err(ApiError::rate_limited(rate_limit));
     }
-}
 
-impl Clone for RateLimiter {
-    fn clone(&self) -> Self {
-        RateLimiter {
-            inner: Arc::clone(&self.inner),
-            max_requests: self.max_requests,
-            window: self.window,
+    let identity = match authenticated {
+        Ok(identity) => identity,
+        Err(auth::AuthError::Invalid) => {
+            warn!("{}", tr!(ApiUnauthorizedLog));
+            return Err(ApiError::unauthorized());
         }
+        Err(auth::AuthError::Expired(name)) => {
+            warn!("{}", tr!(ApiTokenExpiredLog, token = name));
+            return Err(ApiError::token_expired());
+        }
+        Err(auth::AuthError::Stale(name)) => {
+            warn!("{}", tr!(ApiSignatureStaleLog, sender = name));
+            return Err(ApiError::stale_timestamp(tokens.replay_window()));
+        }
+        Err(auth::AuthError::Replayed(name)) => {
+            warn!("{}", tr!(ApiSignatureReplayedLog, sender = name));
+            return Err(ApiError::replayed());
+        }
+    };
+    if let Some(identity) = &identity {
+        if !identity.allows(scope) {
+            warn!(
+                "{}",
+                tr!(ApiForbiddenLog, token = identity.name, scope = scope)
+            );
+            return Err(ApiError::forbidden(scope));
+        }
+        debug!("{}", tr!(ApiAuthorizedLog, token = identity.name));
     }
+    Ok(Access {
+        identity,
+        rate_limit,
+    })
+}
+
+// Spawning is enough to tell whether the binary is there at all.
+fn probe_notify_send() -> Result<(), String> {
+    Command::new("notify-send")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|_| ())
+        .map_err(|e| tr!(NotifySendMissing, error = e))
 }
 
 async fn push_to_homeassistant(
     base_url: &str,
     api_key: &str,
     status: MotionStatus,
+    metrics: &metrics::Metrics,
 ) -> Result<(), Box<dyn std::error::Error>> {
     const MAX_RETRIES: u32 = 5;
     const INITIAL_BACKOFF_MS: u64 = 500;
@@ -260,9 +640,12 @@ async fn push_to_homeassistant(
             Ok(response) => {
                 if response.status().is_success() {
                     info!(
-                        "Successfully pushed motion status to Home Assistant: {} (attempt {})",
-                        status.as_str(),
-                        attempt + 1
+                        "{}",
+                        tr!(
+                            HaPushSucceeded,
+                            status = status.as_str(),
+                            attempt = attempt + 1
+                        )
                     );
                     return Ok(());
                 } else {
@@ -271,125 +654,117 @@ async fn push_to_homeassistant(
                         .text()
                         .await;
                     error!(
-                        "Failed to push to Home Assistant. Status: {} (attempt {})",
-                        status_code,
-                        attempt + 1
+                        "{}",
+                        tr!(HaPushHttpError, status = status_code, attempt = attempt + 1)
                     );
                     last_error = Some(format!("HTTP {}", status_code));
                 }
             }
             Err(e) => {
                 error!(
-                    "Network error pushing to Home Assistant: {} (attempt {})",
-                    e,
-                    attempt + 1
+                    "{}",
+                    tr!(HaPushNetworkError, error = e, attempt = attempt + 1)
                 );
-                last_error = Some(format!("Network error: {}", e));
+                last_error = Some(tr!(HaNetworkError, error = e));
             }
         }
 
         if attempt < MAX_RETRIES - 1 {
             let backoff_ms = INITIAL_BACKOFF_MS * 2_u64.pow(attempt);
-            debug!("Retrying in {}ms...", backoff_ms);
+            debug!("{}", tr!(HaPushRetry, ms = backoff_ms));
+            metrics.homeassistant_retry();
             tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
         }
     }
 
-    Err(format!(
-        "Failed to push to Home Assistant after {} attempts. Last error: {}",
-        MAX_RETRIES,
-        last_error.unwrap_or_else(|| "Unknown error".to_string())
+    Err(tr!(
+        HaPushGaveUp,
+        attempts = MAX_RETRIES,
+        error = last_error.unwrap_or_else(|| tr!(HaUnknownError).to_string()),
     )
     .into())
 }
 
-fn constant_time_eq(a: &str, b: &str) -> bool {
-    let a = a.as_bytes();
-    let b = b.as_bytes();
-    if a.len() != b.len() {
-        return false;
-    }
-    a.iter()
-        .zip(b.iter())
-        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
-        == 0
+// Newer notify-send (libnotify >= 0.8) can report and replace bubble ids.
+fn notify_send_supports_ids() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        Command::new("notify-send")
+            .arg("--help")
+            .stderr(Stdio::null())
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains("--replace-id"))
+            .unwrap_or(false)
+    })
 }
 
-fn parse_interval(interval: &str) -> Result<u64, String> {
-    let len = interval.len();
-    if len < 2 {
-        return Err("Ungültiges Intervallformat".to_string());
-    }
-
-    let (value, unit) = interval.split_at(len - 1);
-    let value: u64 = value
-        .parse()
-        .map_err(|_| "Ungültiger Zahlenwert im Intervall".to_string())?;
-
-    if value == 0 {
-        return Err("Intervall muss größer als 0 sein".to_string());
-    }
-
-    match unit {
-        "s" => Ok(value),
-        "m" => Ok(value.checked_mul(60).ok_or("Intervall-Überlauf")?),
-        "h" => Ok(value.checked_mul(3600).ok_or("Intervall-Überlauf")?),
-        _ => Err("Ungültige Zeiteinheit im Intervall".to_string()),
-    }
-}
-
-fn send_notification(config: &NotificationConfig) {
+// Returns the id of the shown bubble if the backend reports one.
+fn send_notification(notification: &Notification) -> Result<Option<u32>, String> {
+    let supports_ids = notify_send_supports_ids();
     let mut command = Command::new("notify-send");
     command
-        .arg(config.title.as_deref().unwrap_or("Erinnerung"))
-        .arg(&config.message)
-        .stdout(Stdio::null())
+        .arg(&notification.title)
+        .arg(&notification.message)
+        .stdout(if supports_ids {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
         .stderr(Stdio::null());
 
-    if let Some(urgency) = &config.urgency {
+    if let Some(urgency) = notification.urgency {
         command.arg(format!("--urgency={}", urgency));
     }
-    if let Some(expire_time) = config.expire_time {
+    if let Some(expire_time) = notification.expire_time {
         command.arg(format!("--expire-time={}", expire_time));
     }
-    if let Some(app_name) = &config.app_name {
+    if let Some(app_name) = &notification.app_name {
         command.arg(format!("--app-name={}", app_name));
     }
-    if let Some(icon) = &config.icon {
+    if let Some(icon) = &notification.icon {
         command.arg(format!("--icon={}", icon));
     }
-    if let Some(category) = &config.category {
+    if let Some(category) = &notification.category {
         command.arg(format!("--category={}", category));
     }
-    if config.transient.unwrap_or(false) {
+    if notification.transient {
         command.arg("--transient");
     }
+    if supports_ids {
+        command.arg("--print-id");
+        if let Some(id) = notification.replaces_id {
+            command.arg(format!("--replace-id={}", id));
+        }
+    }
 
-    let title = config.title.as_deref().unwrap_or("Erinnerung");
+    let title = &notification.title;
 
     match command.output() {
         Ok(output) if output.status.success() => {
-            info!("Notification sent: {}", title);
+            info!("{}", tr!(NotificationSent, title = title));
+            Ok(String::from_utf8_lossy(&output.stdout).trim().parse().ok())
         }
         Ok(output) => {
-            error!(
-                "notify-send failed with exit code: {:?} (notification: {})",
-                output.status.code(),
-                title
+            let message = tr!(
+                NotifySendFailed,
+                code = format!("{:?}", output.status.code()),
+                title = title,
             );
+            error!("{}", message);
+            Err(message)
         }
         Err(e) => {
-            error!(
-                "Error executing notify-send: {} (notification: {})",
-                e, title
-            );
+            let message = tr!(NotifySendError, error = e, title = title);
+            error!("{}", message);
+            Err(message)
         }
     }
 }
 
-fn create_default_files(config_dir: &PathBuf) -> std::io::Result<()> {
+fn create_default_files(config_path: &Path, notifications_path: &Path) -> std::io::Result<()> {
     let default_config = r#"
     {
+      "version": 2,
       "listen_address": "127.0.0.1",
       "port": 3030,
       "webserver_enabled": true,
@@ -397,172 +772,336 @@ fn create_default_files(config_dir: &PathBuf) -> std::io::Result<()> {
       "api_token": null,
       "rate_limit_rpm": 60,
       "homeassistant_url": null,
-      "homeassistant_api_key": null
-    }
-    "#;
-
-    let default_notifications = r#"
-    [
-      {
-        "title": "Erinnerung",
-        "message": "Trink Wasser!",
-        "interval": "30m",
-        "urgency": "low",
-        "expire_time": 5000,
-        "app_name": "Pushel",
-        "icon": "dialog-information",
-        "category": "reminder",
-        "transient": true
-      },
-      {
+      "homeassistant_api_key": null,
+      "defaults": {
         "title": "Erinnerung",
-        "message": "Mach mal Pause und strecke dich!",
-        "interval": "2h",
-        "urgency": "normal",
-        "expire_time": 5000,
-        "app_name": "Pushel",
-        "icon": "dialog-information",
-        "category": "reminder",
-        "transient": true
-      },
-      {
-        "title": "Erinnerung",
-        "message": "Schau in die Ferne, um deine Augen zu entspannen!",
-        "interval": "40m",
-        "urgency": "low",
-        "expire_time": 5000,
-        "app_name": "Pushel",
-        "icon": "dialog-information",
-        "category": "reminder",
-        "transient": true
-      },
-      {
-        "title": "Erinnerung",
-        "message": "Stehe auf und gehe ein paar Schritte!",
-        "interval": "1h",
-        "urgency": "normal",
-        "expire_time": 5000,
-        "app_name": "Pushel",
-        "icon": "dialog-information",
-        "category": "reminder",
-        "transient": true
-      },
-      {
-        "title": "Erinnerung",
-        "message": "Überprüfe deine Sitzhaltung!",
-        "interval": "15m",
-        "urgency": "low",
         "expire_time": 5000,
         "app_name": "Pushel",
         "icon": "dialog-information",
         "category": "reminder",
         "transient": true
       }
-    ]
+    }
     "#;
 
-    fs::create_dir_all(config_dir)?;
-
-    let config_path = config_dir.join("config.json");
-    let mut config_file = File::create(&config_path)?;
-    config_file.write_all(default_config.as_bytes())?;
-    #[cfg(unix)]
+    let default_notifications = r#"
     {
-        use std::os::unix::fs::PermissionsExt;
-        config_file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+      "version": 1,
+      "notifications": [
+        {
+          "message": "Trink Wasser!",
+          "interval": "30m",
+          "urgency": "low"
+        },
+        {
+          "message": "Mach mal Pause und strecke dich!",
+          "interval": "2h",
+          "urgency": "normal"
+        },
+        {
+          "message": "Schau in die Ferne, um deine Augen zu entspannen!",
+          "interval": "40m",
+          "urgency": "low"
+        },
+        {
+          "message": "Stehe auf und gehe ein paar Schritte!",
+          "interval": "1h",
+          "urgency": "normal"
+        },
+        {
+          "message": "Überprüfe deine Sitzhaltung!",
+          "interval": "15m",
+          "urgency": "low"
+        }
+      ]
     }
+    "#;
 
-    let notifications_path = config_dir.join("notifications.json");
-    let mut notifications_file = File::create(&notifications_path)?;
-    notifications_file.write_all(default_notifications.as_bytes())?;
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        notifications_file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    for (path, contents) in [
+        (config_path, default_config),
+        (notifications_path, default_notifications),
+    ] {
+        if path.exists() {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = config::render_default(path, contents)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        let mut file = File::create(path)?;
+        file.write_all(contents.as_bytes())?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
     }
 
     Ok(())
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+// Only the hash goes into the config; the secret is shown once.
+fn create_token(
+    config_path: &Path,
+    name: String,
+    scopes: Vec<auth::Scope>,
+    expires_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    rate_limit_rpm: Option<u32>,
+) -> i32 {
+    let secret = auth::generate_secret();
+    let mut token = serde_json::json!({
+        "name": name,
+        "hash": auth::hash(&secret),
+        "scopes": scopes,
+    });
+    if let Some(expires_at) = expires_at {
+        token["expires_at"] = serde_json::json!(expires_at);
+    }
+    if let Some(rate_limit_rpm) = rate_limit_rpm {
+        token["rate_limit_rpm"] = serde_json::json!(rate_limit_rpm);
+    }
+    let entry = serde_json::json!({ "api_tokens": [token] });
+    match config::render_default(config_path, &entry.to_string()) {
+        Ok(snippet) => {
+            println!("{}", tr!(TokenCreated, name = name));
+            println!("{secret}\n");
+            println!("{}", tr!(TokenConfigHint, path = config_path.display()));
+            println!("{snippet}");
+            0
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            1
+        }
+    }
+}
+
+fn apply_language(config: &AppConfig) -> Option<String> {
+    let tag = config.language.as_deref()?;
+    match Language::from_tag(tag) {
+        Some(language) => {
+            i18n::set_language(language);
+            None
+        }
+        None => Some(tag.to_string()),
+    }
+}
+
+// Read-only config load for client modes (TUI, run); never migrates on disk.
+fn load_client_config(config_path: &Path, cli: &Cli) -> Option<AppConfig> {
+    if !config_path.exists() {
+        return None;
+    }
+    match config::load_app_config(config_path, false) {
+        Ok(loaded) => {
+            let mut app_config = loaded.value;
+            app_config.apply_overrides(cli);
+            apply_language(&app_config);
+            Some(app_config)
+        }
+        Err(e) => {
+            eprintln!("{}", tr!(ConfigWarning, error = e));
+            None
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Before the runtime starts its threads, as it changes the environment.
+    let listen_fds = listen::systemd_fds();
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(start(listen_fds))
+}
+
+async fn start(listen_fds: Vec<RawFd>) -> Result<(), Box<dyn std::error::Error>> {
     // Initialize X11 for thread-safe operation
     unsafe {
         x11::xlib::XInitThreads();
     }
 
-    let cli = Cli::parse();
-    let config_dir = std::env::var("XDG_CONFIG_HOME")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| {
-            let home = std::env::var("HOME").unwrap_or_else(|_| {
-                eprintln!("Fehler: HOME-Umgebungsvariable ist nicht gesetzt");
-                std::process::exit(1);
-            });
-            let mut path = PathBuf::from(home);
-            path.push(".config");
-            path
-        })
-        .join("pushel");
+    let mut cli = Cli::parse();
+    let config_dir = cli.config_dir.clone().unwrap_or_else(|| {
+        std::env::var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                let home = std::env::var("HOME").unwrap_or_else(|_| {
+                    eprintln!("{}", tr!(HomeNotSet));
+                    std::process::exit(1);
+                });
+                let mut path = PathBuf::from(home);
+                path.push(".config");
+                path
+            })
+            .join("pushel")
+    });
+    let config_path = cli
+        .config
+        .clone()
+        .unwrap_or_else(|| config::find_config_file(&config_dir, "config"));
+    let notifications_path = cli
+        .notifications
+        .clone()
+        .unwrap_or_else(|| config::find_config_file(&config_dir, "notifications"));
+
+    if let Some(command) = cli.command.take() {
+        let app_config = load_client_config(&config_path, &cli);
+        let code = match command {
+            Commands::Run {
+                title,
+                lines,
+                command,
+            } => run::run(command, title, lines, app_config).await,
+            Commands::Send {
+                title,
+                urgency,
+                dedup_key,
+                lines,
+                json,
+                message,
+            } => {
+                let input = match (message.as_str(), lines, json) {
+                    ("-", _, true) => send::Input::JsonLines,
+                    ("-", true, _) => send::Input::Lines,
+                    ("-", ..) => send::Input::Stdin,
+                    _ if lines || json => Cli::command()
+                        .error(
+                            clap::error::ErrorKind::ArgumentConflict,
+                            tr!(SendStdinRequired),
+                        )
+                        .exit(),
+                    _ => send::Input::Text(message),
+                };
+                send::send(input, title, urgency, dedup_key, app_config).await
+            }
+            Commands::Token {
+                name,
+                scopes,
+                expires_at,
+                rate_limit_rpm,
+            } => create_token(&config_path, name, scopes, expires_at, rate_limit_rpm),
+        };
+        std::process::exit(code);
+    }
 
     if cli.tui {
-        if !config_dir.exists() {
-            create_default_files(&config_dir)?;
-        }
-        let notifications_path = config_dir.join("notifications.json");
-        tui::run_tui(notifications_path)?;
+        create_default_files(&config_path, &notifications_path)?;
+        tui::run_tui(notifications_path, load_client_config(&config_path, &cli))?;
         return Ok(());
     }
 
-    if !config_dir.exists() {
+    if !config_path.exists() || !notifications_path.exists() {
         eprintln!(
-            "Erstelle Standardkonfigurationsdateien in {:?}...",
-            config_dir
+            "{}",
+            tr!(
+                CreatingDefaultFiles,
+                config = format!("{:?}", config_path),
+                notifications = format!("{:?}", notifications_path),
+            )
         );
-        create_default_files(&config_dir)?;
+        create_default_files(&config_path, &notifications_path)?;
     }
 
-    let config_path = config_dir.join("config.json");
-    let notifications_path = config_dir.join("notifications.json");
-
-    let config_data = fs::read_to_string(&config_path)?;
-    let app_config: AppConfig = serde_json::from_str(&config_data)?;
+    let loaded_config = config::load_app_config(&config_path, true)?;
+    let mut app_config = loaded_config.value;
+    app_config.apply_overrides(&cli);
+    let unknown_language = apply_language(&app_config);
 
     match app_config.log_format.as_str() {
         "json" => tracing_subscriber::fmt().json().init(),
         _ => tracing_subscriber::fmt().pretty().init(),
     }
 
-    info!("Konfigurationsdatei geladen: {:?}", config_path);
+    info!("{}", tr!(ConfigLoaded, path = format!("{:?}", config_path)));
+    for warning in &loaded_config.warnings {
+        warn!("{}", warning);
+    }
+    if let Some(language) = unknown_language {
+        warn!("{}", tr!(UnknownLanguage, language = language));
+    }
 
-    let notifications_data = fs::read_to_string(&notifications_path)?;
-    let notifications: Vec<NotificationConfig> = serde_json::from_str(&notifications_data)?;
+    let loaded_notifications =
+        config::load_notifications(&notifications_path, true, &app_config.command_allowlist)?;
+    let notifications: Vec<NotificationConfig> = loaded_notifications
+        .value
+        .into_iter()
+        .map(|n| n.with_defaults(&app_config.defaults))
+        .collect();
+
+    info!(
+        "{}",
+        tr!(
+            NotificationsLoaded,
+            path = format!("{:?}", notifications_path)
+        )
+    );
+    for warning in &loaded_notifications.warnings {
+        warn!("{}", warning);
+    }
 
-    info!("Benachrichtigungsdatei geladen: {:?}", notifications_path);
+    let health = health::Health::new();
+    health.ok(health::Component::Config);
+    for warning in loaded_config
+        .warnings
+        .iter()
+        .chain(&loaded_notifications.warnings)
+    {
+        health.error(health::Component::Config, warning);
+    }
+    match probe_notify_send() {
+        Ok(()) => health.ok(health::Component::Notifications),
+        Err(e) => {
+            warn!("{}", e);
+            health.down(health::Component::Notifications, e);
+        }
+    }
+    // Nothing is pushed until the motion status first changes.
+    if app_config.homeassistant_url.is_some() && app_config.homeassistant_api_key.is_some() {
+        health.ok(health::Component::HomeAssistant);
+    } else {
+        health.disabled(health::Component::HomeAssistant);
+    }
 
-    let motion_tracker = MotionTracker::new(tokio::runtime::Handle::current());
+    let events = events::Events::new();
+    let metrics = metrics::Metrics::new();
+    let motion_tracker = MotionTracker::new(
+        tokio::runtime::Handle::current(),
+        events.clone(),
+        metrics.clone(),
+        health.clone(),
+    );
 
     let motion_tracker_idle = motion_tracker.clone();
+    let metrics_idle = metrics.clone();
+    let health_idle = health.clone();
     let ha_url = app_config.homeassistant_url.clone();
     let ha_api_key = app_config.homeassistant_api_key.clone();
 
     thread::spawn(move || {
-        info!("Idle detection thread gestartet");
+        info!("{}", tr!(IdleThreadStarted));
         let mut consecutive_errors = 0u32;
         const MAX_CONSECUTIVE_ERRORS: u32 = 5;
         const ERROR_BACKOFF_SECS: u64 = 30;
 
         let handle_idle_error = |consecutive_errors: &mut u32, error_msg: &str| {
             *consecutive_errors += 1;
+            metrics_idle.idle_error();
+            health_idle.degraded(health::Component::Idle, error_msg);
             error!(
-                "{} (Fehler {}/{})",
-                error_msg, *consecutive_errors, MAX_CONSECUTIVE_ERRORS
+                "{}",
+                tr!(
+                    IdleErrorCount,
+                    error = error_msg,
+                    count = *consecutive_errors,
+                    max = MAX_CONSECUTIVE_ERRORS,
+                )
             );
             if *consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
-                error!(
-                    "Zu viele aufeinanderfolgende Fehler. Pausiere für {}s...",
-                    ERROR_BACKOFF_SECS
-                );
+                let message = tr!(IdleTooManyErrors, secs = ERROR_BACKOFF_SECS);
+                error!("{}", message);
+                health_idle.down(health::Component::Idle, message);
                 thread::sleep(Duration::from_secs(ERROR_BACKOFF_SECS));
                 *consecutive_errors = 0;
             }
@@ -575,6 +1114,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Ok(Ok(idle)) => {
                     consecutive_errors = 0;
                     let idle_seconds = idle.as_seconds();
+                    metrics_idle.set_idle_seconds(idle_seconds);
+                    health_idle.ok(health::Component::Idle);
                     if idle_seconds < 10 {
                         motion_tracker_idle.update_motion();
                         motion_tracker_idle.update_status(
@@ -582,29 +1123,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             ha_url.as_deref(),
                             ha_api_key.as_deref(),
                         );
-                        debug!("User ist aktiv (idle: {}s)", idle_seconds);
+                        debug!("{}", tr!(UserActive, secs = idle_seconds));
                     } else {
                         motion_tracker_idle.update_status(
                             MotionStatus::Inactive,
                             ha_url.as_deref(),
                             ha_api_key.as_deref(),
                         );
-                        debug!("User ist idle ({}s)", idle_seconds);
+                        debug!("{}", tr!(UserIdle, secs = idle_seconds));
                     }
                 }
                 Ok(Err(e)) => {
-                    handle_idle_error(
-                        &mut consecutive_errors,
-                        &format!("Fehler beim Abrufen der Idle-Zeit: {}", e),
-                    );
+                    handle_idle_error(&mut consecutive_errors, &tr!(IdleError, error = e));
                 }
                 Err(panic_info) => {
                     handle_idle_error(
                         &mut consecutive_errors,
-                        &format!(
-                            "PANIC beim Abrufen der Idle-Zeit (vermutlich X11-Fehler): {:?}",
-                            panic_info
-                        ),
+                        &tr!(IdlePanic, error = format!("{:?}", panic_info)),
                     );
                 }
             }
@@ -613,201 +1148,379 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
+    let command_allowlist = Arc::new(app_config.command_allowlist.clone());
+    let history = history::History::new(
+        app_config.history_size,
+        events.clone(),
+        metrics.clone(),
+        health.clone(),
+    );
+    let dispatcher = Dispatcher::new(
+        app_config.coalesce_window.map(Interval::duration),
+        app_config.dedup_ttl.duration(),
+        history.clone(),
+    );
+
     for notif in notifications {
-        let interval = parse_interval(&notif.interval)?;
-        let motion_tracker_clone = motion_tracker.clone();
-
-        thread::spawn(move || {
-            thread::sleep(Duration::from_secs(interval));
-            loop {
-                if motion_tracker_clone.should_notify() {
-                    send_notification(&notif);
-                    info!("Motion detected within the last 15 minutes. Sending notification...");
-                } else {
-                    info!("No motion detected within the last 15 minutes. No notification sent.");
-                }
-                thread::sleep(Duration::from_secs(interval));
-            }
-        });
+        if let Some(watch) = notif.watch.clone() {
+            watch::spawn(
+                notif,
+                watch,
+                Arc::clone(&command_allowlist),
+                motion_tracker.clone(),
+                dispatcher.clone(),
+                health.clone(),
+            )?;
+            continue;
+        }
+        reminder::spawn(
+            notif,
+            Arc::clone(&command_allowlist),
+            motion_tracker.clone(),
+            dispatcher.clone(),
+            events.clone(),
+            health.clone(),
+        );
     }
+    health.ok(health::Component::Scheduler);
 
     // Webserver or idle wait
     if app_config.webserver_enabled {
-        let rate_limiter = Arc::new(RateLimiter::new(app_config.rate_limit_rpm));
-        let api_token = app_config.api_token.clone();
-        let rl = rate_limiter.clone();
-        let token_check = api_token.clone();
-
-        let push = warp::post()
-            .and(warp::path("api"))
-            .and(warp::path("v1"))
-            .and(warp::path("notify"))
-            .and(warp::body::content_length_limit(10 * 1024))
-            .and(warp::addr::remote())
+        let rate_limiter = RateLimiter::new(app_config.rate_limit_rpm, metrics.clone());
+        tokio::spawn(rate_limiter.clone().cleanup());
+        let client = api::client(app_config.trusted_proxies.clone().into());
+        let tokens = auth::Tokens::new(&app_config);
+        let endpoint = NotifyEndpoint {
+            rate_limiter: rate_limiter.clone(),
+            tokens: tokens.clone(),
+            dispatcher,
+            defaults: app_config.defaults.clone(),
+            motion_tracker: motion_tracker.clone(),
+        };
+
+        let push_endpoint = endpoint.clone();
+        let push = warp::path!("api" / "v1" / "notify")
+            .and(warp::post())
+            .and(warp::body::content_length_limit(api::MAX_BODY_BYTES))
+            .and(api::json_content_type())
+            .and(client.clone())
             .and(warp::header::optional::<String>("authorization"))
-            .and(warp::body::json())
-            .map(move |remote: Option<std::net::SocketAddr>, auth_header: Option<String>, notif: AdhocNotification| {
-                if let Some(addr) = remote {
-                    if !rl.check(addr.ip()) {
-                        warn!("Rate limit exceeded for IP: {}", addr.ip());
-                        return warp::reply::with_status(
+            .and(warp::header::optional::<String>(auth::SIGNATURE_HEADER))
+            .and(warp::header::optional::<String>(auth::TIMESTAMP_HEADER))
+            .and(warp::body::bytes())
+            .map(
+                move |remote: Option<IpAddr>,
+                      auth_header: Option<String>,
+                      signature: Option<String>,
+                      timestamp: Option<String>,
+                      body: warp::hyper::body::Bytes| {
+                    let tokens = &push_endpoint.tokens;
+                    // A signature replaces the bearer token, it never adds to it.
+                    let authenticated = match &signature {
+                        Some(signature) => tokens
+                            .verify_signature(signature, timestamp.as_deref(), &body)
+                            .map(Some),
+                        None => tokens.authenticate(auth_header.as_deref()),
+                    };
+                    push_endpoint.submit(
+                        remote,
+                        authenticated,
+                        validate::from_json::<AdhocNotification>(&body),
+                        |_, delivered| {
+                            let message = if delivered {
+                                tr!(ApiNotificationSent)
+                            } else {
+                                tr!(ApiDuplicateSuppressed)
+                            };
                             warp::reply::json(&serde_json::json!({
-                                "status": "error",
-                                "message": "Rate limit exceeded. Try again later."
-                            })),
-                            warp::http::StatusCode::TOO_MANY_REQUESTS,
-                        );
-                    }
-                }
+                                "status": "ok",
+                                "message": message
+                            }))
+                            .into_response()
+                        },
+                    )
+                },
+            );
 
-                if let Some(ref expected_token) = token_check {
-                    let provided = auth_header
-                        .as_deref()
-                        .and_then(|h| h.strip_prefix("Bearer "))
-                        .unwrap_or("");
-                    if !constant_time_eq(provided, expected_token.as_str()) {
-                        warn!("Unauthorized API request");
-                        return warp::reply::with_status(
-                            warp::reply::json(&serde_json::json!({
-                                "status": "error",
-                                "message": "Unauthorized. Provide a valid Bearer token."
-                            })),
-                            warp::http::StatusCode::UNAUTHORIZED,
-                        );
+        // Compatibility routes for clients of other notification services.
+        let ntfy_endpoint = endpoint.clone();
+        let ntfy = compat::enabled(app_config.compat.ntfy)
+            .and(compat::ntfy_topic())
+            .and(client.clone())
+            .and(warp::header::headers_cloned())
+            .and(compat::ntfy_body())
+            .map(
+                move |topic: String,
+                      remote: Option<IpAddr>,
+                      headers: warp::http::HeaderMap,
+                      body: warp::hyper::body::Bytes| {
+                    let secret = compat::ntfy_secret(&headers);
+                    ntfy_endpoint.submit(
+                        remote,
+                        ntfy_endpoint.tokens.authenticate_secret(secret.as_deref()),
+                        compat::ntfy(&topic, &headers, &body),
+                        |notification, _| compat::ntfy_reply(&topic, notification),
+                    )
+                },
+            );
+
+        let gotify_endpoint = endpoint.clone();
+        let gotify = compat::enabled(app_config.compat.gotify)
+            .and(warp::path!("message"))
+            .and(warp::post())
+            .and(warp::body::content_length_limit(api::MAX_BODY_BYTES))
+            .and(client.clone())
+            .and(warp::header::headers_cloned())
+            .and(warp::query::<HashMap<String, String>>())
+            .and(warp::body::bytes())
+            .map(
+                move |remote: Option<IpAddr>,
+                      headers: warp::http::HeaderMap,
+                      query: HashMap<String, String>,
+                      body: warp::hyper::body::Bytes| {
+                    let secret = compat::gotify_secret(&headers, &query);
+                    let message = compat::gotify(&headers, &body);
+                    let priority = message.as_ref().map_or(0, compat::GotifyMessage::priority);
+                    gotify_endpoint.submit(
+                        remote,
+                        gotify_endpoint
+                            .tokens
+                            .authenticate_secret(secret.as_deref()),
+                        message.and_then(compat::GotifyMessage::notification),
+                        |notification, _| compat::gotify_reply(notification, priority),
+                    )
+                },
+            );
+
+        let pushover_endpoint = endpoint.clone();
+        let pushover = compat::enabled(app_config.compat.pushover)
+            .and(warp::path!("1" / "messages.json"))
+            .and(warp::post())
+            .and(warp::body::content_length_limit(api::MAX_BODY_BYTES))
+            .and(client.clone())
+            .and(warp::header::headers_cloned())
+            .and(warp::body::bytes())
+            .map(
+                move |remote: Option<IpAddr>,
+                      headers: warp::http::HeaderMap,
+                      body: warp::hyper::body::Bytes| {
+                    let secret = compat::pushover_secret(&headers, &body);
+                    pushover_endpoint.submit(
+                        remote,
+                        pushover_endpoint
+                            .tokens
+                            .authenticate_secret(secret.as_deref()),
+                        compat::pushover(&headers, &body)
+                            .and_then(compat::PushoverMessage::notification),
+                        |_, _| compat::pushover_reply(),
+                    )
+                },
+            );
+
+        let rl = rate_limiter.clone();
+        let tokens_api = tokens.clone();
+        let history_api = history.clone();
+        // The query is parsed by hand so that a bad filter is reported after
+        // the token check and with the offending parameter in the message.
+        let history_route = warp::path!("api" / "v1" / "history")
+            .and(warp::get())
+            .and(client.clone())
+            .and(warp::header::optional::<String>("authorization"))
+            .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+            .map(
+                move |remote: Option<IpAddr>, auth_header: Option<String>, query: String| {
+                    let access = match check_access(
+                        &rl,
+                        &tokens_api,
+                        auth::Scope::Admin,
+                        remote,
+                        auth_header.as_deref(),
+                    ) {
+                        Ok(access) => access,
+                        Err(error) => return error.into_response(),
+                    };
+                    match serde_urlencoded::from_str::<history::Query>(&query) {
+                        Ok(query) => access.reply(warp::reply::json(&history_api.query(&query))),
+                        Err(e) => access.reply(ApiError::invalid_query(e)),
                     }
-                }
+                },
+            );
 
-                if notif.message.is_empty() {
-                    return warp::reply::with_status(
-                        warp::reply::json(&serde_json::json!({
-                            "status": "error",
-                            "message": "Message cannot be empty"
-                        })),
-                        warp::http::StatusCode::BAD_REQUEST,
-                    );
-                }
-                if notif.message.len() > MAX_MESSAGE_LENGTH {
-                    return warp::reply::with_status(
-                        warp::reply::json(&serde_json::json!({
-                            "status": "error",
-                            "message": format!("Message exceeds maximum length of {MAX_MESSAGE_LENGTH}")
-                        })),
-                        warp::http::StatusCode::BAD_REQUEST,
+        let rl = rate_limiter.clone();
+        let tokens_api = tokens.clone();
+        let events_api = events.clone();
+        let events_route = warp::path!("api" / "v1" / "events")
+            .and(warp::get())
+            .and(client.clone())
+            .and(warp::header::optional::<String>("authorization"))
+            .map(move |remote: Option<IpAddr>, auth_header: Option<String>| {
+                let access = match check_access(
+                    &rl,
+                    &tokens_api,
+                    auth::Scope::Admin,
+                    remote,
+                    auth_header.as_deref(),
+                ) {
+                    Ok(access) => access,
+                    Err(error) => return error.into_response(),
+                };
+                access.reply(warp::sse::reply(
+                    warp::sse::keep_alive().stream(events_api.sse_stream()),
+                ))
+            });
+
+        let rl = rate_limiter.clone();
+        let tokens_api = tokens.clone();
+        let metrics_api = metrics.clone();
+        let metrics_route = warp::path!("metrics")
+            .and(warp::get())
+            .and(client.clone())
+            .and(warp::header::optional::<String>("authorization"))
+            .map(move |remote: Option<IpAddr>, auth_header: Option<String>| {
+                let access = match check_access(
+                    &rl,
+                    &tokens_api,
+                    auth::Scope::Admin,
+                    remote,
+                    auth_header.as_deref(),
+                ) {
+                    Ok(access) => access,
+                    Err(error) => return error.into_response(),
+                };
+                access.reply(warp::reply::with_header(
+                    metrics_api.render(),
+                    "Content-Type",
+                    "text/plain; version=0.0.4",
+                ))
+            });
+
+        // Probes don't require the token and aren't rate limited without one;
+        // error details are only shown to callers that present it. Those
+        // count against the rate limit, so tokens can't be guessed here.
+        let rl = rate_limiter.clone();
+        let tokens_api = tokens.clone();
+        let health_api = health.clone();
+        let health_route = warp::path!("healthz")
+            .map(|| false)
+            .or(warp::path!("readyz").map(|| true))
+            .unify()
+            .and(warp::get())
+            .and(client.clone())
+            .and(warp::header::optional::<String>("authorization"))
+            .map(
+                move |readiness: bool, remote: Option<IpAddr>, auth_header: Option<String>| {
+                    let access = match auth_header {
+                        Some(auth_header) => match check_access(
+                            &rl,
+                            &tokens_api,
+                            auth::Scope::Admin,
+                            remote,
+                            Some(&auth_header),
+                        ) {
+                            Ok(access) => Some(access),
+                            Err(error) if error.is_rate_limited() => return error.into_response(),
+                            Err(_) => None,
+                        },
+                        None => None,
+                    };
+                    let report = health_api.report();
+                    let status = if readiness {
+                        report.ready()
+                    } else {
+                        report.healthy()
+                    };
+                    let report = if access.is_some() || tokens_api.is_empty() {
+                        report
+                    } else {
+                        report.redacted()
+                    };
+                    let reply = warp::reply::with_status(
+                        warp::reply::json(&report),
+                        if status {
+                            warp::http::StatusCode::OK
+                        } else {
+                            warp::http::StatusCode::SERVICE_UNAVAILABLE
+                        },
                     );
-                }
-                if let Some(ref urgency) = notif.urgency {
-                    if !VALID_URGENCIES.contains(&urgency.as_str()) {
-                        return warp::reply::with_status(
-                            warp::reply::json(&serde_json::json!({
-                                "status": "error",
-                                "message": format!("Invalid urgency '{}'. Valid values: {:?}", urgency, VALID_URGENCIES)
-                            })),
-                            warp::http::StatusCode::BAD_REQUEST,
-                        );
+                    match access {
+                        Some(access) => access.reply(reply),
+                        None => reply.into_response(),
                     }
-                }
-                if let Some(ref title) = notif.title {
-                    if title.len() > MAX_FIELD_LENGTH {
-                        return warp::reply::with_status(
-                            warp::reply::json(&serde_json::json!({
-                                "status": "error",
-                                "message": format!("Title exceeds maximum length of {MAX_FIELD_LENGTH}")
-                            })),
-                            warp::http::StatusCode::BAD_REQUEST,
-                        );
-                    }
-                }
-                if let Some(ref app_name) = notif.app_name {
-                    if app_name.len() > MAX_FIELD_LENGTH {
-                        return warp::reply::with_status(
-                            warp::reply::json(&serde_json::json!({
-                                "status": "error",
-                                "message": format!("App name exceeds maximum length of {MAX_FIELD_LENGTH}")
-                            })),
-                            warp::http::StatusCode::BAD_REQUEST,
-                        );
-                    }
-                }
-                if let Some(ref icon) = notif.icon {
-                    if icon.contains('/') || icon.contains("..") {
-                        return warp::reply::with_status(
-                            warp::reply::json(&serde_json::json!({
-                                "status": "error",
-                                "message": "Invalid icon: path separators not allowed"
-                            })),
-                            warp::http::StatusCode::BAD_REQUEST,
-                        );
-                    }
-                    if icon.len() > MAX_FIELD_LENGTH {
-                        return warp::reply::with_status(
-                            warp::reply::json(&serde_json::json!({
-                                "status": "error",
-                                "message": format!("Icon exceeds maximum length of {MAX_FIELD_LENGTH}")
-                            })),
-                            warp::http::StatusCode::BAD_REQUEST,
-                        );
-                    }
-                }
-                if let Some(ref category) = notif.category {
-                    if category.len() > MAX_FIELD_LENGTH {
-                        return warp::reply::with_status(
-                            warp::reply::json(&serde_json::json!({
-                                "status": "error",
-                                "message": format!("Category exceeds maximum length of {MAX_FIELD_LENGTH}")
-                            })),
-                            warp::http::StatusCode::BAD_REQUEST,
-                        );
-                    }
-                }
+                },
+            );
 
-                send_notification(&NotificationConfig::from(notif));
-                warp::reply::with_status(
-                    warp::reply::json(&serde_json::json!({
-                        "status": "ok",
-                        "message": "Notification sent"
-                    })),
-                    warp::http::StatusCode::OK,
-                )
-            })
+        // The spec is public so that clients can be generated without a token.
+        let openapi_route = warp::path!("api" / "v1" / "openapi.json")
+            .and(warp::get())
+            .map(|| warp::reply::with_header(api::OPENAPI, "Content-Type", "application/json"));
+
+        let metrics_log = metrics.clone();
+        let routes = push
+            .or(gotify)
+            .or(pushover)
+            .or(history_route)
+            .or(events_route)
+            .or(metrics_route)
+            .or(health_route)
+            .or(openapi_route)
+            .or(ntfy)
+            .recover(api::handle_rejection)
             .with(warp::reply::with::header(
                 "X-Content-Type-Options",
                 "nosniff",
             ))
-            .with(warp::reply::with::header("X-Frame-Options", "DENY"));
-
-        let address = app_config
-            .listen_address
-            .parse::<std::net::IpAddr>()
-            .map_err(|e| {
-                format!(
-                    "Ungültige listen_address '{}': {}",
-                    app_config.listen_address, e
-                )
-            })?;
-        let socket_addr = std::net::SocketAddr::new(address, app_config.port);
-        if !address.is_loopback() && app_config.api_token.is_none() {
-            warn!(
-                "Webserver bound to non-localhost address {} without API token. Consider setting an api_token in config.json for security.",
-                address
+            .with(warp::reply::with::header("X-Frame-Options", "DENY"))
+            .with(warp::log::custom(move |info| {
+                metrics_log.api_request(info.path(), info.status().as_u16())
+            }));
+
+        // With socket activation, systemd holds the sockets and `listen` is
+        // only used by clients.
+        let mut listeners = listen::Listener::from_systemd(listen_fds)?;
+        if listeners.is_empty() {
+            for address in app_config.listen_addresses()? {
+                listeners.push(listen::Listener::bind(&address).await?);
+            }
+        } else {
+            info!("{}", tr!(SocketActivated, count = listeners.len()));
+        }
+        for address in listeners.iter().filter_map(listen::Listener::tcp_address) {
+            let address = address.ip();
+            if !address.is_loopback() && tokens.is_empty() {
+                warn!("{}", tr!(InsecureBind, address = address));
+            } else if !address.is_loopback() && app_config.tls.is_none() {
+                warn!("{}", tr!(PlaintextBind, address = address));
+            }
+        }
+        let acceptor = app_config.tls.as_ref().map(tls::acceptor).transpose()?;
+        let scheme = if acceptor.is_some() { "https" } else { "http" };
+
+        let service = warp::service(routes);
+        let (shutdown, _) = tokio::sync::broadcast::channel::<()>(1);
+        let mut servers = tokio::task::JoinSet::new();
+        for listener in listeners {
+            info!(
+                "{}",
+                tr!(WebserverStarted, scheme = scheme, address = listener)
             );
+            let mut stopped = shutdown.subscribe();
+            servers.spawn(listen::serve(
+                listener,
+                acceptor.clone(),
+                service.clone(),
+                async move {
+                    let _ = stopped.recv().await;
+                },
+            ));
         }
-        info!(
-            "Webserver gestartet auf http://{}:{}",
-            address, app_config.port
-        );
-
-        let server = warp::serve(push).bind(socket_addr).await;
-        server
-            .graceful(async {
-                tokio::signal::ctrl_c().await.ok();
-                info!("Signal zum Beenden empfangen, fahre Webserver herunter...");
-            })
-            .run()
-            .await;
+        tokio::signal::ctrl_c().await.ok();
+        info!("{}", tr!(WebserverShutdown));
+        let _ = shutdown.send(());
+        servers.join_all().await;
     } else {
-        info!("Webserver deaktiviert. Programm läuft (Ctrl+C zum Beenden)...");
+        info!("{}", tr!(WebserverDisabled));
         tokio::signal::ctrl_c().await.ok();
-        info!("Signal zum Beenden empfangen.");
+        info!("{}", tr!(ShutdownSignal));
     }
 
     Ok(())
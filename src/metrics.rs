@@ -0,0 +1,181 @@
+use crate::history::{Outcome, Source};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+
+// Anything else is counted as `other` so that scanners probing random paths
+// can't blow up the number of series.
+const ROUTES: &[&str] = &[
+    "/api/v1/notify",
+    "/api/v1/history",
+    "/api/v1/events",
+    "/metrics",
+    "/healthz",
+    "/readyz",
+    "/api/v1/openapi.json",
+    "/message",
+    "/1/messages.json",
+];
+
+#[derive(Default)]
+struct Counters {
+    // (source kind, reminder name, outcome)
+    notifications: BTreeMap<(&'static str, String, &'static str), u64>,
+    // (route, status code)
+    api_requests: BTreeMap<(&'static str, u16), u64>,
+    rate_limited: u64,
+    homeassistant_retries: u64,
+    homeassistant_failures: u64,
+    idle_errors: u64,
+    idle_seconds: u64,
+}
+
+// Counters for `/metrics`, rendered in the Prometheus text format.
+pub(crate) struct Metrics {
+    inner: Arc<Mutex<Counters>>,
+}
+
+impl Metrics {
+    pub(crate) fn new() -> Self {
+        Metrics {
+            inner: Arc::new(Mutex::new(Counters::default())),
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Counters)) {
+        f(&mut self.inner.lock().expect("metrics mutex poisoned"));
+    }
+
+    pub(crate) fn notification(&self, source: &Source, outcome: Outcome) {
+        let (kind, name) = match source {
+            Source::Reminder { name } => ("reminder", name.clone()),
+            Source::Watch { name } => ("watch", name.clone()),
+            Source::Api { .. } => ("api", String::new()),
+        };
+        self.update(|c| {
+            *c.notifications
+                .entry((kind, name, outcome.as_str()))
+                .or_default() += 1
+        });
+    }
+
+    pub(crate) fn api_request(&self, path: &str, status: u16) {
+        let route = ROUTES
+            .iter()
+            .copied()
+            .find(|route| *route == path)
+            .unwrap_or("other");
+        self.update(|c| *c.api_requests.entry((route, status)).or_default() += 1);
+    }
+
+    pub(crate) fn rate_limited(&self) {
+        self.update(|c| c.rate_limited += 1);
+    }
+
+    pub(crate) fn homeassistant_retry(&self) {
+        self.update(|c| c.homeassistant_retries += 1);
+    }
+
+    pub(crate) fn homeassistant_failure(&self) {
+        self.update(|c| c.homeassistant_failures += 1);
+    }
+
+    pub(crate) fn idle_error(&self) {
+        self.update(|c| c.idle_errors += 1);
+    }
+
+    pub(crate) fn set_idle_seconds(&self, secs: u64) {
+        self.update(|c| c.idle_seconds = secs);
+    }
+
+    pub(crate) fn render(&self) -> String {
+        let c = self.inner.lock().expect("metrics mutex poisoned");
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "pushel_notifications_total",
+            "counter",
+            "Notifications by source, reminder and outcome.",
+        );
+        for ((source, reminder, outcome), count) in &c.notifications {
+            let _ = writeln!(
+                out,
+                "pushel_notifications_total{{source=\"{source}\",reminder=\"{}\",outcome=\"{outcome}\"}} {count}",
+                escape(reminder),
+            );
+        }
+
+        header(
+            &mut out,
+            "pushel_api_requests_total",
+            "counter",
+            "HTTP requests by route and status code.",
+        );
+        for ((route, code), count) in &c.api_requests {
+            let _ = writeln!(
+                out,
+                "pushel_api_requests_total{{route=\"{route}\",code=\"{code}\"}} {count}"
+            );
+        }
+
+        for (name, kind, help, value) in [
+            (
+                "pushel_rate_limited_total",
+                "counter",
+                "Requests rejected by the rate limiter.",
+                c.rate_limited,
+            ),
+            (
+                "pushel_homeassistant_push_retries_total",
+                "counter",
+                "Home Assistant pushes that were retried.",
+                c.homeassistant_retries,
+            ),
+            (
+                "pushel_homeassistant_push_failures_total",
+                "counter",
+                "Home Assistant pushes that failed after all retries.",
+                c.homeassistant_failures,
+            ),
+            (
+                "pushel_idle_detection_errors_total",
+                "counter",
+                "Failed idle time queries.",
+                c.idle_errors,
+            ),
+            (
+                "pushel_idle_seconds",
+                "gauge",
+                "Seconds since the last keyboard or mouse input.",
+                c.idle_seconds,
+            ),
+        ] {
+            header(&mut out, name, kind, help);
+            let _ = writeln!(out, "{name} {value}");
+        }
+        out
+    }
+}
+
+impl Clone for Metrics {
+    fn clone(&self) -> Self {
+        Metrics {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
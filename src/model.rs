@@ -0,0 +1,441 @@
+use crate::i18n::tr;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr, time::Duration};
+
+// Ordered from least to most urgent.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Urgency {
+    Low,
+    Normal,
+    Critical,
+}
+
+impl Urgency {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Urgency::Low => "low",
+            Urgency::Normal => "normal",
+            Urgency::Critical => "critical",
+        }
+    }
+}
+
+impl fmt::Display for Urgency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// A positive duration such as `30m`, `1h30m`, `90min`, `2d` or the ISO-8601
+// form `PT1H30M`, optionally followed by a random jitter (`45m±5m`, or
+// `45m+-5m` for keyboards without `±`). Parsed once at deserialization so a
+// bad value fails the load instead of a worker thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct Interval {
+    secs: u64,
+    jitter: u64,
+}
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+// One year. Longer waits are surely a typo, and larger values would overflow
+// once added to an `Instant`.
+const MAX_SECS: u64 = 365 * DAY;
+
+impl Interval {
+    pub(crate) const fn from_secs(secs: u64) -> Self {
+        Interval { secs, jitter: 0 }
+    }
+
+    pub(crate) fn duration(self) -> Duration {
+        Duration::from_secs(self.secs)
+    }
+
+    // The interval shifted by a random amount within the jitter, drawn anew
+    // for every wait so that reminders drift apart.
+    pub(crate) fn next_delay(self) -> Duration {
+        if self.jitter == 0 {
+            return self.duration();
+        }
+        let earliest = self.secs - self.jitter;
+        let latest = self.secs.saturating_add(self.jitter);
+        Duration::from_secs(rand::random_range(earliest..=latest))
+    }
+}
+
+impl FromStr for Interval {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, String> {
+        let separator = input
+            .find('±')
+            .map(|i| (i, '±'.len_utf8()))
+            .or_else(|| input.find("+-").map(|i| (i, 2)));
+        let (secs, jitter) = match separator {
+            Some((i, len)) => (
+                parse_duration(input, 0, &input[..i])?,
+                parse_duration(input, i + len, &input[i + len..])?,
+            ),
+            None => (parse_duration(input, 0, input)?, 0),
+        };
+        if jitter >= secs {
+            return Err(tr!(
+                IntervalJitterTooLarge,
+                jitter = format_secs(jitter),
+                interval = format_secs(secs),
+            ));
+        }
+        Ok(Interval { secs, jitter })
+    }
+}
+
+// `offset` is where `text` starts within `input`, so errors can point at the
+// offending character of what the user actually wrote.
+fn parse_duration(input: &str, offset: usize, text: &str) -> Result<u64, String> {
+    let trimmed = text.trim_start();
+    let offset = offset + text.len() - trimmed.len();
+    let text = trimmed.trim_end();
+    if text.is_empty() {
+        return Err(tr!(IntervalEmpty).to_string());
+    }
+    let secs = if text.starts_with(['P', 'p']) {
+        parse_iso8601(input, offset, text)?
+    } else {
+        parse_compound(input, offset, text)?
+    };
+    if secs == 0 {
+        return Err(tr!(IntervalZero).to_string());
+    }
+    if secs > MAX_SECS {
+        return Err(too_long(input, offset));
+    }
+    Ok(secs)
+}
+
+fn too_long(input: &str, offset: usize) -> String {
+    tr!(
+        IntervalTooLong,
+        pos = position(input, offset),
+        input = input,
+        max = format!("{}d", MAX_SECS / DAY),
+    )
+}
+
+// 1-based character position, as shown to the user.
+fn position(input: &str, byte: usize) -> usize {
+    input[..byte].chars().count() + 1
+}
+
+fn parse_compound(input: &str, offset: usize, text: &str) -> Result<u64, String> {
+    let mut total: u64 = 0;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let at = offset + text.len() - rest.len();
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            return Err(tr!(
+                IntervalExpectedNumber,
+                pos = position(input, at),
+                input = input,
+                found = rest.chars().next().unwrap_or_default(),
+            ));
+        }
+        let (number, after) = rest.split_at(digits);
+        let after = after.trim_start();
+        let unit_at = offset + text.len() - after.len();
+        let unit_len = after.len() - after.trim_start_matches(char::is_alphabetic).len();
+        let (unit, after) = after.split_at(unit_len);
+        if unit.is_empty() {
+            return Err(tr!(
+                IntervalMissingUnit,
+                number = number,
+                pos = position(input, unit_at),
+                input = input,
+            ));
+        }
+        let factor = match unit {
+            "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            "m" | "min" | "mins" | "minute" | "minutes" => MINUTE,
+            "h" | "hr" | "hrs" | "hour" | "hours" => HOUR,
+            "d" | "day" | "days" => DAY,
+            "w" | "week" | "weeks" => WEEK,
+            _ => {
+                return Err(tr!(
+                    IntervalUnknownUnit,
+                    unit = unit,
+                    pos = position(input, unit_at),
+                    input = input,
+                ))
+            }
+        };
+        total = number
+            .parse::<u64>()
+            .ok()
+            .and_then(|n| n.checked_mul(factor))
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(|| too_long(input, offset))?;
+        rest = after.trim_start();
+    }
+    Ok(total)
+}
+
+// `P[nW][nD][T[nH][nM][nS]]`; years and months are rejected as they have no
+// fixed length.
+fn parse_iso8601(input: &str, offset: usize, text: &str) -> Result<u64, String> {
+    let unexpected = |at: usize, found: char| {
+        tr!(
+            IntervalIsoUnexpected,
+            found = found,
+            pos = position(input, offset + at),
+            input = input,
+        )
+    };
+    let mut total: u64 = 0;
+    let mut in_time = false;
+    let mut number: Option<(usize, u64)> = None;
+    let mut last = (0, 'P');
+
+    for (at, c) in text.char_indices().skip(1) {
+        last = (at, c);
+        if let Some(digit) = c.to_digit(10) {
+            let start = number.map(|(start, _)| start).unwrap_or(at);
+            let value = number.map(|(_, value)| value).unwrap_or(0);
+            let value = value
+                .checked_mul(10)
+                .and_then(|v| v.checked_add(u64::from(digit)))
+                .ok_or_else(|| too_long(input, offset))?;
+            number = Some((start, value));
+            continue;
+        }
+        let factor = match (c.to_ascii_uppercase(), in_time, number) {
+            ('T', false, None) => {
+                in_time = true;
+                continue;
+            }
+            ('W', false, Some(_)) => WEEK,
+            ('D', false, Some(_)) => DAY,
+            ('H', true, Some(_)) => HOUR,
+            ('M', true, Some(_)) => MINUTE,
+            ('S', true, Some(_)) => 1,
+            _ => return Err(unexpected(at, c)),
+        };
+        let (_, value) = number.take().unwrap_or_default();
+        total = value
+            .checked_mul(factor)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(|| too_long(input, offset))?;
+    }
+
+    if let Some((start, _)) = number {
+        return Err(tr!(
+            IntervalMissingUnit,
+            number = &text[start..],
+            pos = position(input, offset + text.len()),
+            input = input,
+        ));
+    }
+    // A bare `P` or a dangling `T` has nothing to count.
+    if matches!(last.1, 'P' | 'p' | 'T' | 't') {
+        return Err(unexpected(last.0, last.1));
+    }
+    Ok(total)
+}
+
+fn format_secs(secs: u64) -> String {
+    let mut out = String::new();
+    let mut rest = secs;
+    for (unit, size) in [
+        ("w", WEEK),
+        ("d", DAY),
+        ("h", HOUR),
+        ("m", MINUTE),
+        ("s", 1),
+    ] {
+        if rest >= size {
+            out.push_str(&format!("{}{unit}", rest / size));
+            rest %= size;
+        }
+    }
+    if out.is_empty() {
+        out.push_str("0s");
+    }
+    out
+}
+
+impl TryFrom<String> for Interval {
+    type Error = String;
+
+    fn try_from(interval: String) -> Result<Self, String> {
+        interval.parse()
+    }
+}
+
+impl From<Interval> for String {
+    fn from(interval: Interval) -> Self {
+        interval.to_string()
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format_secs(self.secs))?;
+        if self.jitter > 0 {
+            write!(f, "±{}", format_secs(self.jitter))?;
+        }
+        Ok(())
+    }
+}
+
+// What actually reaches the desktop: defaults applied and templates rendered.
+#[derive(Debug, Clone)]
+pub(crate) struct Notification {
+    pub(crate) title: String,
+    pub(crate) message: String,
+    pub(crate) urgency: Option<Urgency>,
+    pub(crate) expire_time: Option<u32>,
+    pub(crate) app_name: Option<String>,
+    pub(crate) icon: Option<String>,
+    pub(crate) category: Option<String>,
+    pub(crate) transient: bool,
+    // Bubble to update in place instead of showing a new one.
+    pub(crate) replaces_id: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::{set_language, Language};
+
+    fn parse(input: &str) -> Result<Interval, String> {
+        set_language(Language::En);
+        input.parse()
+    }
+
+    fn secs(input: &str) -> u64 {
+        parse(input).unwrap().secs
+    }
+
+    #[test]
+    fn parses_single_units() {
+        assert_eq!(secs("45s"), 45);
+        assert_eq!(secs("30m"), 30 * MINUTE);
+        assert_eq!(secs("2h"), 2 * HOUR);
+        assert_eq!(secs("1d"), DAY);
+        assert_eq!(secs("1w"), WEEK);
+        assert_eq!(secs("90min"), 90 * MINUTE);
+        assert_eq!(secs("3 hours"), 3 * HOUR);
+    }
+
+    #[test]
+    fn parses_compound_durations() {
+        assert_eq!(secs("1h30m"), HOUR + 30 * MINUTE);
+        assert_eq!(secs("1d 12h"), DAY + 12 * HOUR);
+        assert_eq!(secs(" 2m 30s "), 2 * MINUTE + 30);
+    }
+
+    #[test]
+    fn parses_iso8601_durations() {
+        assert_eq!(secs("PT1H30M"), HOUR + 30 * MINUTE);
+        assert_eq!(secs("P1D"), DAY);
+        assert_eq!(secs("P1W"), WEEK);
+        assert_eq!(secs("P1DT2H"), DAY + 2 * HOUR);
+        assert_eq!(secs("pt45s"), 45);
+    }
+
+    #[test]
+    fn parses_jitter() {
+        let interval = parse("45m±5m").unwrap();
+        assert_eq!((interval.secs, interval.jitter), (45 * MINUTE, 5 * MINUTE));
+        assert_eq!(parse("45m+-5m").unwrap(), interval);
+        assert_eq!(parse("PT45M ± PT5M").unwrap(), interval);
+        assert_eq!(interval.to_string(), "45m±5m");
+        for _ in 0..100 {
+            let delay = interval.next_delay().as_secs();
+            assert!((40 * MINUTE..=50 * MINUTE).contains(&delay));
+        }
+    }
+
+    #[test]
+    fn displays_normalized() {
+        assert_eq!(parse("90min").unwrap().to_string(), "1h30m");
+        assert_eq!(parse("P1DT1S").unwrap().to_string(), "1d1s");
+    }
+
+    #[test]
+    fn rejects_jitter_not_smaller_than_interval() {
+        assert_eq!(
+            parse("5m±5m").unwrap_err(),
+            "Jitter 5m must be smaller than the interval 5m"
+        );
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        assert_eq!(
+            parse("5x").unwrap_err(),
+            "Unknown time unit 'x' at position 2 of '5x' (use s, m, h, d or w)"
+        );
+        assert_eq!(
+            parse("1h x").unwrap_err(),
+            "Expected a number at position 4 of '1h x', found 'x'"
+        );
+        assert_eq!(
+            parse("1h30").unwrap_err(),
+            "Missing time unit after '30' at position 5 of '1h30'"
+        );
+        assert_eq!(
+            parse("P1Y").unwrap_err(),
+            "Unexpected 'Y' at position 3 of ISO-8601 duration 'P1Y'"
+        );
+        assert_eq!(
+            parse("PT").unwrap_err(),
+            "Unexpected 'T' at position 2 of ISO-8601 duration 'PT'"
+        );
+        // Positions count characters, so `±` is one.
+        assert_eq!(
+            parse("45m±x").unwrap_err(),
+            "Expected a number at position 5 of '45m±x', found 'x'"
+        );
+    }
+
+    #[test]
+    fn rejects_empty_and_zero() {
+        assert_eq!(parse("").unwrap_err(), "Interval is empty");
+        assert_eq!(parse("0s").unwrap_err(), "Interval must be greater than 0");
+        assert_eq!(
+            parse("PT0S").unwrap_err(),
+            "Interval must be greater than 0"
+        );
+    }
+
+    #[test]
+    fn rejects_intervals_above_one_year() {
+        assert_eq!(secs("365d"), MAX_SECS);
+        assert_eq!(
+            parse("366d").unwrap_err(),
+            "Duration at position 1 of '366d' exceeds the maximum of 365d"
+        );
+        assert_eq!(
+            parse("18446744073709551615s").unwrap_err(),
+            "Duration at position 1 of '18446744073709551615s' exceeds the maximum of 365d"
+        );
+        assert_eq!(
+            parse("99999999999999999999w").unwrap_err(),
+            "Duration at position 1 of '99999999999999999999w' exceeds the maximum of 365d"
+        );
+        assert_eq!(
+            parse("P99999999999999999999D").unwrap_err(),
+            "Duration at position 1 of 'P99999999999999999999D' exceeds the maximum of 365d"
+        );
+        assert_eq!(
+            parse("1d±400d").unwrap_err(),
+            "Duration at position 4 of '1d±400d' exceeds the maximum of 365d"
+        );
+    }
+}
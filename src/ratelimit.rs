@@ -0,0 +1,150 @@
+use crate::metrics::Metrics;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use warp::{http::HeaderValue, reply::Response};
+
+// How often buckets that have refilled completely are dropped.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Client {
+    Address(IpAddr),
+    Token(String),
+    // Connections without an address share one bucket rather than bypassing
+    // the limit.
+    Unknown,
+}
+
+// A bucket holds up to `rpm` requests and refills continuously at `rpm` per
+// minute, so bursts are allowed but the sustained rate is capped.
+struct Bucket {
+    tokens: f64,
+    rpm: u32,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_second(self.rpm)).min(f64::from(self.rpm));
+        self.updated = now;
+    }
+
+    fn is_full(&self) -> bool {
+        self.tokens >= f64::from(self.rpm)
+    }
+
+    // Time until `tokens` requests are available again.
+    fn wait_for(&self, tokens: f64) -> Duration {
+        let missing = (tokens - self.tokens).max(0.0);
+        Duration::from_secs_f64(missing / per_second(self.rpm))
+    }
+}
+
+fn per_second(rpm: u32) -> f64 {
+    f64::from(rpm) / 60.0
+}
+
+// Outcome of a check, reported to the client as `X-RateLimit-*` headers.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RateLimit {
+    pub(crate) allowed: bool,
+    limit: u32,
+    remaining: u32,
+    // Until the bucket is full again.
+    reset: Duration,
+    // Until the next request would be allowed.
+    retry_after: Duration,
+}
+
+impl RateLimit {
+    pub(crate) fn apply(&self, response: &mut Response) {
+        let headers = response.headers_mut();
+        headers.insert("X-RateLimit-Limit", HeaderValue::from(self.limit));
+        headers.insert("X-RateLimit-Remaining", HeaderValue::from(self.remaining));
+        headers.insert(
+            "X-RateLimit-Reset",
+            HeaderValue::from(ceil_secs(self.reset)),
+        );
+        if !self.allowed {
+            headers.insert(
+                "Retry-After",
+                HeaderValue::from(ceil_secs(self.retry_after)),
+            );
+        }
+    }
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+pub(crate) struct RateLimiter {
+    inner: Arc<Mutex<HashMap<Client, Bucket>>>,
+    pub(crate) max_requests: u32,
+    metrics: Metrics,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(rpm: u32, metrics: Metrics) -> Self {
+        RateLimiter {
+            inner: Arc::new(Mutex::new(HashMap::new())),
+            max_requests: rpm,
+            metrics,
+        }
+    }
+
+    pub(crate) fn check(&self, client: Client, rpm: u32) -> RateLimit {
+        let mut map = self.inner.lock().expect("rate limiter mutex poisoned");
+        let now = Instant::now();
+        let bucket = map.entry(client).or_insert(Bucket {
+            tokens: f64::from(rpm),
+            rpm,
+            updated: now,
+        });
+        bucket.refill(now);
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        } else {
+            self.metrics.rate_limited();
+        }
+        RateLimit {
+            allowed,
+            limit: rpm,
+            remaining: bucket.tokens as u32,
+            reset: bucket.wait_for(f64::from(rpm)),
+            retry_after: bucket.wait_for(1.0),
+        }
+    }
+
+    // Forgets clients whose bucket has refilled, keeping the map small without
+    // scanning it on every request.
+    pub(crate) async fn cleanup(self) {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = Instant::now();
+            let mut map = self.inner.lock().expect("rate limiter mutex poisoned");
+            map.retain(|_, bucket| {
+                bucket.refill(now);
+                !bucket.is_full()
+            });
+        }
+    }
+}
+
+impl Clone for RateLimiter {
+    fn clone(&self) -> Self {
+        RateLimiter {
+            inner: Arc::clone(&self.inner),
+            max_requests: self.max_requests,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
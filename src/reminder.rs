@@ -0,0 +1,120 @@
+use crate::{
+    config,
+    dispatch::Dispatcher,
+    events::{Event, Events},
+    health::{Component, Health},
+    history::Source,
+    i18n::tr,
+    template, MotionTracker, NotificationConfig,
+};
+use chrono::{Local, TimeDelta};
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+use tracing::{error, info};
+
+// Gives the idle detection thread time for its first sample, otherwise a
+// reminder firing right at startup would always see an absent user.
+const STARTUP_GRACE: Duration = Duration::from_secs(1);
+
+pub(crate) fn spawn(
+    notif: NotificationConfig,
+    allowlist: Arc<Vec<PathBuf>>,
+    motion_tracker: MotionTracker,
+    dispatcher: Dispatcher,
+    events: Events,
+    health: Health,
+) {
+    thread::spawn(move || {
+        let title = notif.title.as_deref().unwrap_or(config::DEFAULT_TITLE);
+        let name = notif.name();
+        let origin = Source::Reminder {
+            name: name.to_string(),
+        };
+        let started = Instant::now();
+        let mut today = Local::now().date_naive();
+        let mut fired_today = 0u32;
+        let mut occurrences = 0u32;
+
+        wait(
+            name,
+            title,
+            if notif.fire_on_start {
+                STARTUP_GRACE
+            } else {
+                notif.initial_delay.unwrap_or(notif.interval).next_delay()
+            },
+            &events,
+        );
+        loop {
+            if notif
+                .stop_after
+                .is_some_and(|stop_after| started.elapsed() >= stop_after.duration())
+            {
+                break;
+            }
+            if Local::now().date_naive() != today {
+                today = Local::now().date_naive();
+                fired_today = 0;
+            }
+
+            if notif.max_per_day.is_some_and(|max| fired_today >= max) {
+                info!("{}", tr!(ReminderDailyLimit, title = title));
+                let vars = template::builtin_vars(motion_tracker.active_minutes());
+                dispatcher.suppress(&notif.render(&vars), origin.clone(), "daily_limit");
+            } else if motion_tracker.should_notify() {
+                let output = match &notif.command {
+                    Some(source) => source.run(&allowlist).unwrap_or_else(|e| {
+                        error!("{}", e);
+                        health.error(Component::Scheduler, e);
+                        None
+                    }),
+                    None => Some(String::new()),
+                };
+                match output {
+                    Some(output) => {
+                        fired_today += 1;
+                        occurrences += 1;
+                        let mut vars = template::builtin_vars(motion_tracker.active_minutes());
+                        vars.insert("count".to_string(), fired_today.to_string());
+                        vars.insert("output".to_string(), output);
+                        dispatcher.submit(notif.render(&vars), origin.clone(), None);
+                        info!("{}", tr!(MotionNotify));
+                        if notif.max_occurrences.is_some_and(|max| occurrences >= max) {
+                            break;
+                        }
+                    }
+                    None => {
+                        info!("{}", tr!(CommandSkipped, title = title));
+                    }
+                }
+            } else {
+                info!("{}", tr!(MotionSkip));
+                let vars = template::builtin_vars(motion_tracker.active_minutes());
+                dispatcher.suppress(&notif.render(&vars), origin.clone(), "away");
+            }
+            wait(name, title, notif.interval.next_delay(), &events);
+        }
+        info!("{}", tr!(ReminderFinished, title = title));
+        events.publish(Event::Schedule {
+            name: name.to_string(),
+            title: title.to_string(),
+            next_at: None,
+        });
+    });
+}
+
+// Announces when the reminder is due next, then sleeps until then.
+fn wait(name: &str, title: &str, delay: Duration, events: &Events) {
+    events.publish(Event::Schedule {
+        name: name.to_string(),
+        title: title.to_string(),
+        next_at: TimeDelta::from_std(delay)
+            .ok()
+            .and_then(|delay| Local::now().checked_add_signed(delay)),
+    });
+    thread::sleep(delay);
+}
@@ -0,0 +1,170 @@
+use crate::{
+    client, config::AppConfig, i18n::tr, model::Urgency, AdhocNotification, MAX_MESSAGE_LENGTH,
+};
+use std::{
+    collections::VecDeque,
+    io::{BufRead, BufReader, Read, Write},
+    process::{Command, ExitStatus, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+type Tail = Arc<Mutex<VecDeque<String>>>;
+
+pub(crate) async fn run(
+    command: Vec<String>,
+    title: Option<String>,
+    lines: usize,
+    config: Option<AppConfig>,
+) -> i32 {
+    let Some((program, args)) = command.split_first() else {
+        return 2;
+    };
+    let mut display = command.join(" ");
+    if display.chars().count() > 80 {
+        display = display.chars().take(79).chain(['…']).collect();
+    }
+
+    // The child shares our process group and receives Ctrl+C itself; we stay
+    // alive to report how it ended.
+    tokio::spawn(async {
+        loop {
+            if tokio::signal::ctrl_c().await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let started = Instant::now();
+    let child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("{}", tr!(CommandSpawnFailed, program = program, error = e));
+            return 127;
+        }
+    };
+
+    let tail: Tail = Arc::new(Mutex::new(VecDeque::with_capacity(lines)));
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|out| tee(out, std::io::stdout(), Arc::clone(&tail), lines)),
+        child
+            .stderr
+            .take()
+            .map(|err| tee(err, std::io::stderr(), Arc::clone(&tail), lines)),
+    ];
+
+    let status = tokio::task::spawn_blocking(move || child.wait())
+        .await
+        .ok()
+        .and_then(Result::ok);
+    for reader in readers.into_iter().flatten() {
+        let _ = reader.join();
+    }
+    let elapsed = started.elapsed();
+    let code = status.map(exit_code).unwrap_or(1);
+
+    let output = tail
+        .lock()
+        .map(|tail| tail.iter().cloned().collect::<Vec<_>>().join("\n"))
+        .unwrap_or_default();
+    let mut message = tr!(RunSummary, duration = format_duration(elapsed), code = code);
+    if !output.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(&output);
+    }
+    if message.len() > MAX_MESSAGE_LENGTH {
+        let mut cut = message.len() - MAX_MESSAGE_LENGTH + '…'.len_utf8();
+        while !message.is_char_boundary(cut) {
+            cut += 1;
+        }
+        message = format!("…{}", &message[cut..]);
+    }
+
+    let notification = AdhocNotification {
+        title: Some(title.unwrap_or_else(|| {
+            if code == 0 {
+                tr!(RunSucceeded, command = display)
+            } else {
+                tr!(RunFailed, command = display)
+            }
+        })),
+        message,
+        urgency: Some(if code == 0 {
+            Urgency::Normal
+        } else {
+            Urgency::Critical
+        }),
+        expire_time: None,
+        app_name: None,
+        icon: None,
+        category: Some(
+            if code == 0 {
+                "transfer.complete"
+            } else {
+                "transfer.error"
+            }
+            .to_string(),
+        ),
+        transient: None,
+        dedup_key: None,
+        variables: Default::default(),
+    };
+    if let Err(e) = client::deliver(notification, config.as_ref()).await {
+        eprintln!("{}", e);
+    }
+    code
+}
+
+fn tee<R, W>(source: R, mut sink: W, tail: Tail, lines: usize) -> thread::JoinHandle<()>
+where
+    R: Read + Send + 'static,
+    W: Write + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut reader = BufReader::new(source);
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line).unwrap_or(0) > 0 {
+            let _ = sink.write_all(&line);
+            let _ = sink.flush();
+            if let Ok(mut tail) = tail.lock() {
+                if tail.len() == lines {
+                    tail.pop_front();
+                }
+                if lines > 0 {
+                    tail.push_back(String::from_utf8_lossy(&line).trim_end().to_string());
+                }
+            }
+            line.clear();
+        }
+    })
+}
+
+fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match (secs / 3600, secs % 3600 / 60, secs % 60) {
+        (0, 0, s) => format!("{}.{:01}s", s, duration.subsec_millis() / 100),
+        (0, m, s) => format!("{m}m {s}s"),
+        (h, m, s) => format!("{h}h {m}m {s}s"),
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{client, config::AppConfig, i18n::tr, model::Urgency, validate, AdhocNotification};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+
+pub(crate) enum Input {
+    Text(String),
+    Stdin,
+    Lines,
+    JsonLines,
+}
+
+// Returns the exit code: 0 if everything was delivered, 1 otherwise. Bad lines
+// are reported and skipped so a long-running pipe keeps going.
+pub(crate) async fn send(
+    input: Input,
+    title: Option<String>,
+    urgency: Option<Urgency>,
+    dedup_key: Option<String>,
+    config: Option<AppConfig>,
+) -> i32 {
+    let plain = |message: String| AdhocNotification {
+        title: title.clone(),
+        message,
+        urgency,
+        expire_time: None,
+        app_name: None,
+        icon: None,
+        category: None,
+        transient: None,
+        dedup_key: dedup_key.clone(),
+        variables: Default::default(),
+    };
+
+    let message = match input {
+        Input::Text(message) => message,
+        Input::Stdin => {
+            let mut message = String::new();
+            if let Err(e) = tokio::io::stdin().read_to_string(&mut message).await {
+                eprintln!("{}", tr!(SendReadFailed, error = e));
+                return 1;
+            }
+            message.trim_end().to_string()
+        }
+        Input::Lines | Input::JsonLines => {
+            let json = matches!(input, Input::JsonLines);
+            let mut lines = BufReader::new(tokio::io::stdin()).lines();
+            let mut number = 0;
+            let mut failed = false;
+            loop {
+                let line = match lines.next_line().await {
+                    Ok(Some(line)) => line,
+                    Ok(None) => break,
+                    Err(e) => {
+                        eprintln!("{}", tr!(SendReadFailed, error = e));
+                        return 1;
+                    }
+                };
+                number += 1;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let notification = if json {
+                    match validate::from_json::<AdhocNotification>(line.as_bytes()) {
+                        Ok(notification) => notification,
+                        Err(e) => {
+                            eprintln!("{}", tr!(SendInvalidRecord, line = number, error = e));
+                            failed = true;
+                            continue;
+                        }
+                    }
+                } else {
+                    plain(line)
+                };
+                if let Err(e) = client::deliver(notification, config.as_ref()).await {
+                    eprintln!("{}", tr!(SendInvalidRecord, line = number, error = e));
+                    failed = true;
+                }
+            }
+            return i32::from(failed);
+        }
+    };
+
+    match client::deliver(plain(message), config.as_ref()).await {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
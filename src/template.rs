@@ -0,0 +1,54 @@
+use chrono::Local;
+use std::collections::HashMap;
+
+pub(crate) type Vars = HashMap<String, String>;
+
+pub(crate) fn builtin_vars(active_minutes: u64) -> Vars {
+    let now = Local::now();
+    let hostname = hostname::get()
+        .ok()
+        .and_then(|h| h.into_string().ok())
+        .unwrap_or_default();
+
+    HashMap::from([
+        ("time".to_string(), now.format("%H:%M").to_string()),
+        ("date".to_string(), now.format("%Y-%m-%d").to_string()),
+        ("weekday".to_string(), now.format("%A").to_string()),
+        ("hostname".to_string(), hostname),
+        ("active_minutes".to_string(), active_minutes.to_string()),
+    ])
+}
+
+// Replaces `{name}` with the matching variable. Unknown placeholders are kept
+// verbatim so that literal braces in messages survive untouched.
+pub(crate) fn render(template: &str, vars: &Vars) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) if is_identifier(&after[..end]) => match vars.get(&after[..end]) {
+                Some(value) => {
+                    out.push_str(value);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = after;
+                }
+            },
+            _ => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
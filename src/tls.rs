@@ -0,0 +1,54 @@
+use crate::{config::TlsConfig, i18n::tr};
+use std::{path::Path, sync::Arc};
+use tokio_rustls::{
+    rustls::{
+        crypto::{ring, CryptoProvider},
+        pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+        server::WebPkiClientVerifier,
+        RootCertStore, ServerConfig,
+    },
+    TlsAcceptor,
+};
+
+pub(crate) fn acceptor(config: &TlsConfig) -> Result<TlsAcceptor, String> {
+    let provider = Arc::new(ring::default_provider());
+    let certs = read_certs(&config.cert_path)?;
+    let key = PrivateKeyDer::from_pem_file(&config.key_path)
+        .map_err(|e| tr!(TlsKeyInvalid, path = config.key_path.display(), error = e))?;
+
+    let builder = ServerConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?;
+    let builder = match &config.client_ca_path {
+        Some(path) => builder.with_client_cert_verifier(client_verifier(path, provider)?),
+        None => builder.with_no_client_auth(),
+    };
+    let mut server_config = builder
+        .with_single_cert(certs, key)
+        .map_err(|e| tr!(TlsCertInvalid, path = config.cert_path.display(), error = e))?;
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, String> {
+    CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| tr!(TlsCertInvalid, path = path.display(), error = e))
+}
+
+// Only clients with a certificate issued by the bundle get past the handshake.
+fn client_verifier(
+    path: &Path,
+    provider: Arc<CryptoProvider>,
+) -> Result<Arc<dyn tokio_rustls::rustls::server::danger::ClientCertVerifier>, String> {
+    let invalid = |error: &dyn std::fmt::Display| {
+        tr!(TlsClientCaInvalid, path = path.display(), error = error)
+    };
+    let mut roots = RootCertStore::empty();
+    for cert in read_certs(path)? {
+        roots.add(cert).map_err(|e| invalid(&e))?;
+    }
+    WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+        .build()
+        .map_err(|e| invalid(&e))
+}
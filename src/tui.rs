@@ -1,4 +1,11 @@
-use crate::NotificationConfig;
+use crate::{
+    client,
+    config::{self, AppConfig, NotificationDefaults},
+    history::{Entry, Outcome},
+    i18n::{t, tr, Msg},
+    model::Urgency,
+    NotificationConfig,
+};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
     execute,
@@ -12,16 +19,20 @@ use ratatui::{
     widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
     Frame, Terminal,
 };
-use std::{io, path::PathBuf, process::Command};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
-pub fn run_tui(notifications_path: PathBuf) -> io::Result<()> {
+pub fn run_tui(notifications_path: PathBuf, config: Option<AppConfig>) -> io::Result<()> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let app = App::new(notifications_path);
+    let app = App::new(notifications_path, config);
     let res = run_app(&mut terminal, app);
 
     disable_raw_mode()?;
@@ -33,22 +44,40 @@ pub fn run_tui(notifications_path: PathBuf) -> io::Result<()> {
     terminal.show_cursor()?;
 
     if let Err(err) = res {
-        eprintln!("TUI error: {err:?}");
+        eprintln!("{}", tr!(TuiError, error = format!("{err:?}")));
     }
     Ok(())
 }
 
+#[derive(PartialEq)]
+enum View {
+    Notifications,
+    History,
+}
+
 struct App {
     notifications_path: PathBuf,
+    config: Option<AppConfig>,
+    defaults: NotificationDefaults,
     notifications: Vec<NotificationConfig>,
     table_state: TableState,
     selected_index: usize,
+    view: View,
+    history: Vec<Entry>,
+    history_total: usize,
+    history_state: TableState,
     status_message: String,
 }
 
 impl App {
-    fn new(notifications_path: PathBuf) -> Self {
-        let notifications = load_notifications(&notifications_path);
+    fn new(notifications_path: PathBuf, config: Option<AppConfig>) -> Self {
+        let defaults = config
+            .as_ref()
+            .map(|c| c.defaults.clone())
+            .unwrap_or_default();
+        let allowlist = config.as_ref().map_or(&[][..], |c| &c.command_allowlist);
+        let (notifications, status_message) =
+            load_notifications(&notifications_path, &defaults, allowlist);
         let len = notifications.len();
         let mut table_state = TableState::default();
         if len > 0 {
@@ -56,14 +85,30 @@ impl App {
         }
         App {
             notifications_path,
+            config,
+            defaults,
             notifications,
             table_state,
             selected_index: 0,
-            status_message: String::new(),
+            view: View::Notifications,
+            history: Vec::new(),
+            history_total: 0,
+            history_state: TableState::default(),
+            status_message,
         }
     }
 
     fn next(&mut self) {
+        if self.view == View::History {
+            if !self.history.is_empty() {
+                let i = self
+                    .history_state
+                    .selected()
+                    .map_or(0, |i| (i + 1).min(self.history.len() - 1));
+                self.history_state.select(Some(i));
+            }
+            return;
+        }
         if self.notifications.is_empty() {
             return;
         }
@@ -76,6 +121,16 @@ impl App {
     }
 
     fn prev(&mut self) {
+        if self.view == View::History {
+            if !self.history.is_empty() {
+                let i = self
+                    .history_state
+                    .selected()
+                    .map_or(0, |i| i.saturating_sub(1));
+                self.history_state.select(Some(i));
+            }
+            return;
+        }
         if self.notifications.is_empty() {
             return;
         }
@@ -85,7 +140,14 @@ impl App {
     }
 
     fn reload(&mut self) {
-        self.notifications = load_notifications(&self.notifications_path);
+        let allowlist = self
+            .config
+            .as_ref()
+            .map_or(&[][..], |c| &c.command_allowlist);
+        let (notifications, status_message) =
+            load_notifications(&self.notifications_path, &self.defaults, allowlist);
+        self.notifications = notifications;
+        self.status_message = status_message;
         if self.selected_index >= self.notifications.len() && !self.notifications.is_empty() {
             self.selected_index = self.notifications.len() - 1;
             self.table_state.select(Some(self.selected_index));
@@ -97,6 +159,40 @@ impl App {
         }
     }
 
+    fn toggle_history(&mut self) {
+        if self.view == View::History {
+            self.view = View::Notifications;
+            return;
+        }
+        self.view = View::History;
+        self.reload_history();
+    }
+
+    // The history lives in the daemon, so it is fetched over its API.
+    fn reload_history(&mut self) {
+        let result = match &self.config {
+            Some(config) => tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(client::fetch_history(config))
+            }),
+            None => Err(tr!(TuiHistoryNeedsWebserver).to_string()),
+        };
+        match result {
+            Ok(page) => {
+                self.history = page.entries;
+                self.history_total = page.total;
+                self.history_state
+                    .select((!self.history.is_empty()).then_some(0));
+                self.status_message.clear();
+            }
+            Err(e) => {
+                self.history.clear();
+                self.history_total = 0;
+                self.history_state.select(None);
+                self.status_message = tr!(TuiHistoryUnavailable, error = e);
+            }
+        }
+    }
+
     fn open_editor(&mut self) {
         const SAFE_EDITORS: &[&str] = &["vi", "vim", "nvim", "nano", "emacs", "code", "hx", "helix"];
 
@@ -106,9 +202,10 @@ impl App {
             .and_then(|n| n.to_str())
             .unwrap_or("");
         if !SAFE_EDITORS.contains(&editor_name) {
-            self.status_message = format!(
-                "Editor '{editor}' is not in the safe list. Allowed: {}",
-                SAFE_EDITORS.join(", ")
+            self.status_message = tr!(
+                TuiEditorNotAllowed,
+                editor = editor,
+                allowed = SAFE_EDITORS.join(", "),
             );
             return;
         }
@@ -119,31 +216,39 @@ impl App {
         match result {
             Ok(status) if status.success() => {
                 self.reload();
-                self.status_message = format!("Editor '{editor}' closed. Notifications reloaded.");
+                if self.status_message.is_empty() {
+                    self.status_message = tr!(TuiEditorClosed, editor = editor);
+                }
             }
             Ok(status) => {
-                self.status_message = format!(
-                    "Editor '{editor}' exited with code {}.",
-                    status.code().unwrap_or(-1)
+                self.status_message = tr!(
+                    TuiEditorExitCode,
+                    editor = editor,
+                    code = status.code().unwrap_or(-1),
                 );
             }
             Err(e) => {
-                self.status_message = format!("Failed to launch editor '{editor}': {e}");
+                self.status_message = tr!(TuiEditorLaunchFailed, editor = editor, error = e);
             }
         }
     }
 }
 
-fn load_notifications(path: &PathBuf) -> Vec<NotificationConfig> {
-    match std::fs::read_to_string(path) {
-        Ok(data) => serde_json::from_str(&data).unwrap_or_else(|e| {
-            eprintln!("Warning: Failed to parse notifications file '{}': {}", path.display(), e);
-            Vec::new()
-        }),
-        Err(e) => {
-            eprintln!("Warning: Failed to read notifications file '{}': {}", path.display(), e);
-            Vec::new()
-        }
+fn load_notifications(
+    path: &Path,
+    defaults: &NotificationDefaults,
+    allowlist: &[PathBuf],
+) -> (Vec<NotificationConfig>, String) {
+    match config::load_notifications(path, false, allowlist) {
+        Ok(loaded) => (
+            loaded
+                .value
+                .into_iter()
+                .map(|n| n.with_defaults(defaults))
+                .collect(),
+            loaded.warnings.join("; "),
+        ),
+        Err(e) => (Vec::new(), e),
     }
 }
 
@@ -157,6 +262,13 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, mut app: App)
                     KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
                     KeyCode::Char('j') | KeyCode::Down => app.next(),
                     KeyCode::Char('k') | KeyCode::Up => app.prev(),
+                    KeyCode::Char('h') => app.toggle_history(),
+                    KeyCode::Char('r') if app.view == View::History => {
+                        app.reload_history();
+                        if app.status_message.is_empty() {
+                            app.status_message = tr!(TuiHistoryReloaded).to_string();
+                        }
+                    }
                     KeyCode::Char('e') => {
                         disable_raw_mode()?;
                         execute!(
@@ -178,7 +290,9 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, mut app: App)
                     }
                     KeyCode::Char('r') => {
                         app.reload();
-                        app.status_message = "Notifications reloaded.".to_string();
+                        if app.status_message.is_empty() {
+                            app.status_message = tr!(TuiReloaded).to_string();
+                        }
                     }
                     _ => {}
                 }
@@ -200,7 +314,7 @@ fn ui(f: &mut Frame, app: &mut App) {
 
     let header = Paragraph::new(Line::from(vec![
         Span::styled(
-            "Pushel Notification Manager",
+            tr!(TuiTitle),
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
@@ -214,8 +328,13 @@ fn ui(f: &mut Frame, app: &mut App) {
     .block(Block::default().borders(Borders::ALL));
     f.render_widget(header, main_layout[0]);
 
-    render_table(f, app, main_layout[1]);
-    render_details(f, app, main_layout[2]);
+    if app.view == View::History {
+        render_history_table(f, app, main_layout[1]);
+        render_history_details(f, app, main_layout[2]);
+    } else {
+        render_table(f, app, main_layout[1]);
+        render_details(f, app, main_layout[2]);
+    }
     render_help(f, app, main_layout[3]);
 }
 
@@ -228,29 +347,34 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
         Constraint::Length(10),
     ];
 
-    let header_cells = ["#", "Title", "Message", "Interval", "Urgency"]
-        .iter()
-        .map(|h| Cell::from(*h).style(Style::default().add_modifier(Modifier::BOLD)));
+    let header_cells = [
+        "#",
+        tr!(TuiTitleLabel),
+        tr!(TuiMessageLabel),
+        tr!(TuiIntervalLabel),
+        tr!(TuiUrgencyLabel),
+    ]
+    .into_iter()
+    .map(|h| Cell::from(h).style(Style::default().add_modifier(Modifier::BOLD)));
 
     let header = Row::new(header_cells)
         .style(Style::default().fg(Color::White).bg(Color::DarkGray))
         .height(1);
 
     let rows = app.notifications.iter().enumerate().map(|(i, n)| {
-        let msg = truncate_str(&n.message, 35);
-        let urgency = n.urgency.as_deref().unwrap_or("-");
-        let urgency_style = match urgency {
-            "critical" => Style::default().fg(Color::Red),
-            "normal" => Style::default().fg(Color::Yellow),
+        let msg = truncate_str(&message_summary(n), 35);
+        let urgency_style = match n.urgency {
+            Some(Urgency::Critical) => Style::default().fg(Color::Red),
+            Some(Urgency::Normal) => Style::default().fg(Color::Yellow),
             _ => Style::default().fg(Color::Green),
         };
 
         Row::new(vec![
             Cell::from(format!("{}", i + 1)),
-            Cell::from(n.title.as_deref().unwrap_or("Erinnerung")),
+            Cell::from(n.title.as_deref().unwrap_or(&app.defaults.title)),
             Cell::from(msg),
-            Cell::from(n.interval.as_str()),
-            Cell::from(urgency).style(urgency_style),
+            Cell::from(n.interval.to_string()),
+            Cell::from(urgency_label(n.urgency)).style(urgency_style),
         ])
         .height(1)
     });
@@ -260,7 +384,7 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Notifications"),
+                .title(tr!(TuiNotifications)),
         )
         .highlight_style(
             Style::default()
@@ -276,10 +400,10 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
 fn render_details(f: &mut Frame, app: &App, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
-        .title(format!("Details: #{}", app.selected_index + 1));
+        .title(tr!(TuiDetails, index = app.selected_index + 1));
 
     if app.notifications.is_empty() || app.selected_index >= app.notifications.len() {
-        let p = Paragraph::new("No notification selected")
+        let p = Paragraph::new(tr!(TuiNoSelection))
             .block(block)
             .style(Style::default().fg(Color::DarkGray));
         f.render_widget(p, area);
@@ -289,38 +413,23 @@ fn render_details(f: &mut Frame, app: &App, area: Rect) {
     let n = &app.notifications[app.selected_index];
     let lines = vec![
         Line::from(vec![
-            Span::styled(
-                "  Title:        ",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
+            detail_label(Msg::TuiTitleLabel),
             Span::raw(n.title.as_deref().unwrap_or("-")),
         ]),
         Line::from(vec![
-            Span::styled(
-                "  Message:      ",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(&n.message),
+            detail_label(Msg::TuiMessageLabel),
+            Span::raw(message_summary(n)),
         ]),
         Line::from(vec![
-            Span::styled(
-                "  Interval:     ",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(&n.interval),
+            detail_label(Msg::TuiIntervalLabel),
+            Span::raw(n.interval.to_string()),
         ]),
         Line::from(vec![
-            Span::styled(
-                "  Urgency:      ",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(n.urgency.as_deref().unwrap_or("-")),
+            detail_label(Msg::TuiUrgencyLabel),
+            Span::raw(urgency_label(n.urgency)),
         ]),
         Line::from(vec![
-            Span::styled(
-                "  Expire Time:  ",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
+            detail_label(Msg::TuiExpireTimeLabel),
             Span::raw(
                 n.expire_time
                     .map(|t| t.to_string())
@@ -328,31 +437,19 @@ fn render_details(f: &mut Frame, app: &App, area: Rect) {
             ),
         ]),
         Line::from(vec![
-            Span::styled(
-                "  App Name:     ",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
+            detail_label(Msg::TuiAppNameLabel),
             Span::raw(n.app_name.as_deref().unwrap_or("-")),
         ]),
         Line::from(vec![
-            Span::styled(
-                "  Icon:         ",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
+            detail_label(Msg::TuiIconLabel),
             Span::raw(n.icon.as_deref().unwrap_or("-")),
         ]),
         Line::from(vec![
-            Span::styled(
-                "  Category:     ",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
+            detail_label(Msg::TuiCategoryLabel),
             Span::raw(n.category.as_deref().unwrap_or("-")),
         ]),
         Line::from(vec![
-            Span::styled(
-                "  Transient:    ",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
+            detail_label(Msg::TuiTransientLabel),
             Span::raw(
                 n.transient
                     .map(|t| t.to_string())
@@ -365,15 +462,125 @@ fn render_details(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(p, area);
 }
 
+fn render_history_table(f: &mut Frame, app: &mut App, area: Rect) {
+    let widths = [
+        Constraint::Length(14),
+        Constraint::Length(22),
+        Constraint::Length(10),
+        Constraint::Length(18),
+        Constraint::Min(20),
+    ];
+
+    let header_cells = [
+        tr!(TuiTimeLabel),
+        tr!(TuiSourceLabel),
+        tr!(TuiOutcomeLabel),
+        tr!(TuiTitleLabel),
+        tr!(TuiMessageLabel),
+    ]
+    .into_iter()
+    .map(|h| Cell::from(h).style(Style::default().add_modifier(Modifier::BOLD)));
+
+    let header = Row::new(header_cells)
+        .style(Style::default().fg(Color::White).bg(Color::DarkGray))
+        .height(1);
+
+    let rows = app.history.iter().map(|e| {
+        Row::new(vec![
+            Cell::from(e.timestamp.format("%m-%d %H:%M:%S").to_string()),
+            Cell::from(e.source.to_string()),
+            Cell::from(e.outcome.as_str()).style(outcome_style(e.outcome)),
+            Cell::from(e.title.as_str()),
+            Cell::from(truncate_str(&e.message.replace('\n', " "), 35)),
+        ])
+        .height(1)
+    });
+
+    let t = Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(tr!(TuiHistory, total = app.history_total)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+
+    f.render_stateful_widget(t, area, &mut app.history_state);
+}
+
+fn render_history_details(f: &mut Frame, app: &App, area: Rect) {
+    let selected = app.history_state.selected();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(tr!(TuiDetails, index = selected.map_or(0, |i| i + 1)));
+
+    let Some(e) = selected.and_then(|i| app.history.get(i)) else {
+        let p = Paragraph::new(tr!(TuiNoHistoryEntry))
+            .block(block)
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(p, area);
+        return;
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            detail_label(Msg::TuiTimeLabel),
+            Span::raw(e.timestamp.format("%Y-%m-%d %H:%M:%S").to_string()),
+        ]),
+        Line::from(vec![
+            detail_label(Msg::TuiSourceLabel),
+            Span::raw(e.source.to_string()),
+        ]),
+        Line::from(vec![
+            detail_label(Msg::TuiOutcomeLabel),
+            Span::styled(e.outcome.as_str(), outcome_style(e.outcome)),
+        ]),
+        Line::from(vec![
+            detail_label(Msg::TuiReasonLabel),
+            Span::raw(e.reason.as_deref().unwrap_or("-")),
+        ]),
+        Line::from(vec![
+            detail_label(Msg::TuiUrgencyLabel),
+            Span::raw(urgency_label(e.urgency)),
+        ]),
+        Line::from(vec![
+            detail_label(Msg::TuiTitleLabel),
+            Span::raw(e.title.as_str()),
+        ]),
+    ];
+    let mut message = e.message.lines();
+    lines.push(Line::from(vec![
+        detail_label(Msg::TuiMessageLabel),
+        Span::raw(message.next().unwrap_or_default()),
+    ]));
+    lines.extend(message.map(|line| Line::from(format!("  {:<15}{line}", ""))));
+
+    let p = Paragraph::new(lines).block(block);
+    f.render_widget(p, area);
+}
+
 fn render_help(f: &mut Frame, app: &App, area: Rect) {
+    let toggle = if app.view == View::History {
+        tr!(TuiHelpNotifications)
+    } else {
+        tr!(TuiHelpHistory)
+    };
     let mut spans = vec![
-        Span::styled("[↑↓/jk] Navigate", Style::default().fg(Color::Cyan)),
+        Span::styled(tr!(TuiHelpNavigate), Style::default().fg(Color::Cyan)),
         Span::raw("  "),
-        Span::styled("[e] Edit in $EDITOR", Style::default().fg(Color::Cyan)),
+        Span::styled(tr!(TuiHelpEdit), Style::default().fg(Color::Cyan)),
         Span::raw("  "),
-        Span::styled("[r] Reload", Style::default().fg(Color::Cyan)),
+        Span::styled(tr!(TuiHelpReload), Style::default().fg(Color::Cyan)),
         Span::raw("  "),
-        Span::styled("[q/Esc] Quit", Style::default().fg(Color::Cyan)),
+        Span::styled(toggle, Style::default().fg(Color::Cyan)),
+        Span::raw("  "),
+        Span::styled(tr!(TuiHelpQuit), Style::default().fg(Color::Cyan)),
     ];
 
     if !app.status_message.is_empty() {
@@ -388,6 +595,38 @@ fn render_help(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(p, area);
 }
 
+fn message_summary(n: &NotificationConfig) -> String {
+    match n.messages.as_slice() {
+        [] if n.message.is_empty() => n
+            .command
+            .as_ref()
+            .map(|c| format!("$ {} {}", c.program.display(), c.args.join(" ")))
+            .unwrap_or_default(),
+        [] => n.message.clone(),
+        [only] => only.clone(),
+        [first, rest @ ..] => format!("{first} (+{})", rest.len()),
+    }
+}
+
+fn urgency_label(urgency: Option<Urgency>) -> &'static str {
+    urgency.map(Urgency::as_str).unwrap_or("-")
+}
+
+fn outcome_style(outcome: Outcome) -> Style {
+    match outcome {
+        Outcome::Delivered => Style::default().fg(Color::Green),
+        Outcome::Suppressed => Style::default().fg(Color::Yellow),
+        Outcome::Failed => Style::default().fg(Color::Red),
+    }
+}
+
+fn detail_label(msg: Msg) -> Span<'static> {
+    Span::styled(
+        format!("  {:<15}", format!("{}:", t(msg))),
+        Style::default().add_modifier(Modifier::BOLD),
+    )
+}
+
 fn truncate_str(s: &str, max_len: usize) -> String {
     if s.chars().count() > max_len {
         let mut truncated: String = s.chars().take(max_len.saturating_sub(1)).collect();
@@ -0,0 +1,427 @@
+use crate::{
+    auth::HASH_PREFIX,
+    command::{self, CommandSource},
+    config::{
+        ApiTokenConfig, AppConfig, NotificationDefaults, NotificationsFile, WebhookSenderConfig,
+    },
+    i18n::tr,
+    AdhocNotification, NotificationConfig, MAX_FIELD_LENGTH, MAX_MESSAGE_LENGTH,
+};
+use regex::Regex;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::error::Category;
+use std::{collections::HashSet, fmt, path::PathBuf};
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FieldError {
+    pub(crate) field: String,
+    // Stable identifier for clients; `message` is localized.
+    pub(crate) code: &'static str,
+    pub(crate) message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub(crate) struct ValidationErrors(pub(crate) Vec<FieldError>);
+
+impl ValidationErrors {
+    // Qualifies every field, e.g. `urgency` -> `notifications[2].urgency`.
+    pub(crate) fn within(mut self, prefix: &str) -> Self {
+        for error in &mut self.0 {
+            error.field = format!("{prefix}.{}", error.field);
+        }
+        self
+    }
+
+    pub(crate) fn single(
+        field: impl Into<String>,
+        code: &'static str,
+        message: impl Into<String>,
+    ) -> Self {
+        ValidationErrors(vec![FieldError {
+            field: field.into(),
+            code,
+            message: message.into(),
+        }])
+    }
+
+    fn from_path<E: fmt::Display>(
+        error: serde_path_to_error::Error<E>,
+        code: &'static str,
+    ) -> Self {
+        ValidationErrors(vec![FieldError {
+            field: error.path().to_string(),
+            code,
+            message: error.into_inner().to_string(),
+        }])
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}: {}", error.field, error.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<ValidationErrors> for String {
+    fn from(errors: ValidationErrors) -> Self {
+        errors.to_string()
+    }
+}
+
+// Type errors (e.g. an unknown urgency) carry the path of the offending field.
+impl<E: fmt::Display> From<serde_path_to_error::Error<E>> for ValidationErrors {
+    fn from(error: serde_path_to_error::Error<E>) -> Self {
+        ValidationErrors::from_path(error, "invalid")
+    }
+}
+
+// Anything shorter is within reach of an offline brute force on one captured
+// signature.
+const MIN_WEBHOOK_SECRET_LENGTH: usize = 32;
+
+pub(crate) trait Validate {
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+#[derive(Default)]
+struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    fn error(&mut self, field: impl Into<String>, code: &'static str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.into(),
+            code,
+            message: message.into(),
+        });
+    }
+
+    fn message(&mut self, field: impl Into<String>, message: &str) {
+        if message.is_empty() {
+            self.error(field, "empty", tr!(ValidateEmpty));
+        } else if message.len() > MAX_MESSAGE_LENGTH {
+            self.error(
+                field,
+                "too_long",
+                tr!(ValidateTooLong, max = MAX_MESSAGE_LENGTH),
+            );
+        }
+    }
+
+    fn length(&mut self, field: &str, value: Option<&str>) {
+        if value.is_some_and(|v| v.len() > MAX_FIELD_LENGTH) {
+            self.error(
+                field,
+                "too_long",
+                tr!(ValidateTooLong, max = MAX_FIELD_LENGTH),
+            );
+        }
+    }
+
+    // Icons are theme names; a path could point notify-send at arbitrary files.
+    fn icon(&mut self, icon: Option<&str>) {
+        if icon.is_some_and(|i| i.contains('/') || i.contains("..")) {
+            self.error("icon", "icon_path", tr!(ValidateIconPath));
+        }
+        self.length("icon", icon);
+    }
+
+    fn display_fields(
+        &mut self,
+        title: Option<&str>,
+        app_name: Option<&str>,
+        icon: Option<&str>,
+        category: Option<&str>,
+    ) {
+        self.length("title", title);
+        self.length("app_name", app_name);
+        self.icon(icon);
+        self.length("category", category);
+    }
+
+    fn nested(&mut self, prefix: &str, result: Result<(), ValidationErrors>) {
+        if let Err(errors) = result {
+            self.errors.extend(errors.within(prefix).0);
+        }
+    }
+
+    fn finish(self) -> Result<(), ValidationErrors> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors(self.errors))
+        }
+    }
+}
+
+impl Validate for AdhocNotification {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut v = Validator::default();
+        v.message("message", &self.message);
+        v.length("dedup_key", self.dedup_key.as_deref());
+        v.display_fields(
+            self.title.as_deref(),
+            self.app_name.as_deref(),
+            self.icon.as_deref(),
+            self.category.as_deref(),
+        );
+        v.finish()
+    }
+}
+
+impl Validate for NotificationConfig {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut v = Validator::default();
+        if self.name.as_deref() == Some("") {
+            v.error("name", "empty", tr!(ValidateEmpty));
+        }
+        v.length("name", self.name.as_deref());
+        // Command reminders and watchers may leave the text to `{output}`.
+        let output_only = self.command.is_some() || self.watch.is_some();
+        if !(self.message.is_empty() && (output_only || !self.messages.is_empty())) {
+            v.message("message", &self.message);
+        }
+        for (i, message) in self.messages.iter().enumerate() {
+            v.message(format!("messages[{i}]"), message);
+        }
+        if self.fire_on_start && self.initial_delay.is_some() {
+            v.error(
+                "fire_on_start",
+                "conflict",
+                tr!(ValidateConflict, other = "initial_delay"),
+            );
+        }
+        for (field, max) in [
+            ("max_occurrences", self.max_occurrences),
+            ("max_per_day", self.max_per_day),
+        ] {
+            if max == Some(0) {
+                v.error(field, "not_positive", tr!(ValidatePositive));
+            }
+        }
+        if let Some(command) = &self.command {
+            v.nested("command", command.validate());
+        }
+        if let Some(watch) = &self.watch {
+            let conditions = [
+                watch.file_exists.is_some(),
+                watch.file_changed.is_some(),
+                watch.process_exited.is_some(),
+                watch.http_failing.is_some(),
+                watch.command_matches.is_some(),
+            ];
+            if conditions.into_iter().filter(|set| *set).count() != 1 {
+                v.error("watch", "invalid_condition", tr!(WatchInvalidCondition));
+            }
+            if let Some(matcher) = &watch.command_matches {
+                if let Err(e) = Regex::new(&matcher.pattern) {
+                    v.error(
+                        "watch.command_matches.pattern",
+                        "invalid_pattern",
+                        tr!(WatchInvalidPattern, pattern = matcher.pattern, error = e),
+                    );
+                }
+                v.nested("watch.command_matches.command", matcher.command.validate());
+            }
+        }
+        if self.watch.is_some() {
+            let schedule = [
+                ("initial_delay", self.initial_delay.is_some()),
+                ("fire_on_start", self.fire_on_start),
+                ("max_occurrences", self.max_occurrences.is_some()),
+                ("max_per_day", self.max_per_day.is_some()),
+                ("stop_after", self.stop_after.is_some()),
+            ];
+            for (field, _) in schedule.into_iter().filter(|(_, set)| *set) {
+                v.error(field, "timer_only", tr!(ValidateTimerOnly));
+            }
+        }
+        v.display_fields(
+            self.title.as_deref(),
+            self.app_name.as_deref(),
+            self.icon.as_deref(),
+            self.category.as_deref(),
+        );
+        v.finish()
+    }
+}
+
+impl NotificationsFile {
+    // Not `Validate`: the allowlist lives in the app config.
+    pub(crate) fn validate_with(&self, allowlist: &[PathBuf]) -> Result<(), ValidationErrors> {
+        let mut v = Validator::default();
+        let mut names = HashSet::new();
+        for (i, notification) in self.notifications.iter().enumerate() {
+            let prefix = format!("notifications[{i}]");
+            v.nested(&prefix, notification.validate());
+            // Unnamed reminders are named after their position when loaded.
+            let name = notification.name.clone().unwrap_or_else(|| i.to_string());
+            if !names.insert(name) {
+                v.error(
+                    format!("{prefix}.name"),
+                    "duplicate",
+                    tr!(ValidateDuplicate),
+                );
+            }
+            let matcher = notification
+                .watch
+                .as_ref()
+                .and_then(|w| w.command_matches.as_ref());
+            for (field, command) in [
+                ("command", notification.command.as_ref()),
+                ("watch.command_matches.command", matcher.map(|m| &m.command)),
+            ] {
+                if let Some(Err(e)) = command.map(|c| c.check_allowed(allowlist)) {
+                    v.error(format!("{prefix}.{field}.program"), "not_allowed", e);
+                }
+            }
+        }
+        v.finish()
+    }
+}
+
+impl Validate for CommandSource {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut v = Validator::default();
+        for name in self
+            .env
+            .keys()
+            .filter(|name| !command::is_allowed_env(name))
+        {
+            v.error(
+                format!("env.{name}"),
+                "not_allowed",
+                tr!(ValidateEnvName, name = name),
+            );
+        }
+        v.finish()
+    }
+}
+
+impl Validate for NotificationDefaults {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut v = Validator::default();
+        v.display_fields(
+            Some(&self.title),
+            self.app_name.as_deref(),
+            self.icon.as_deref(),
+            self.category.as_deref(),
+        );
+        v.finish()
+    }
+}
+
+impl Validate for ApiTokenConfig {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut v = Validator::default();
+        if self.name.is_empty() {
+            v.error("name", "empty", tr!(ValidateEmpty));
+        }
+        v.length("name", Some(&self.name));
+        let digest = self.hash.strip_prefix(HASH_PREFIX).unwrap_or_default();
+        if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+            v.error("hash", "invalid_hash", tr!(ValidateTokenHash));
+        }
+        if self.scopes.is_empty() {
+            v.error("scopes", "empty", tr!(ValidateEmpty));
+        }
+        if self.rate_limit_rpm == Some(0) {
+            v.error("rate_limit_rpm", "not_positive", tr!(ValidatePositive));
+        }
+        v.finish()
+    }
+}
+
+impl Validate for WebhookSenderConfig {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut v = Validator::default();
+        if self.name.is_empty() {
+            v.error("name", "empty", tr!(ValidateEmpty));
+        }
+        v.length("name", Some(&self.name));
+        if self.secret.len() < MIN_WEBHOOK_SECRET_LENGTH {
+            v.error(
+                "secret",
+                "too_short",
+                tr!(ValidateTooShort, min = MIN_WEBHOOK_SECRET_LENGTH),
+            );
+        }
+        v.finish()
+    }
+}
+
+impl Validate for AppConfig {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut v = Validator::default();
+        v.nested("defaults", self.defaults.validate());
+        if self.rate_limit_rpm == 0 {
+            v.error("rate_limit_rpm", "not_positive", tr!(ValidatePositive));
+        }
+        let mut names = HashSet::new();
+        for (i, token) in self.api_tokens.iter().enumerate() {
+            let prefix = format!("api_tokens[{i}]");
+            v.nested(&prefix, token.validate());
+            if !names.insert(token.name.as_str()) {
+                v.error(
+                    format!("{prefix}.name"),
+                    "duplicate",
+                    tr!(ValidateDuplicate),
+                );
+            }
+        }
+        // Senders share the namespace so history and logs stay unambiguous.
+        for (i, sender) in self.webhook_senders.iter().enumerate() {
+            let prefix = format!("webhook_senders[{i}]");
+            v.nested(&prefix, sender.validate());
+            if !names.insert(sender.name.as_str()) {
+                v.error(
+                    format!("{prefix}.name"),
+                    "duplicate",
+                    tr!(ValidateDuplicate),
+                );
+            }
+        }
+        if self.webhook_replay_window.duration().is_zero() {
+            v.error(
+                "webhook_replay_window",
+                "not_positive",
+                tr!(ValidatePositive),
+            );
+        }
+        v.finish()
+    }
+}
+
+pub(crate) fn from_json<T: DeserializeOwned + Validate>(
+    json: &[u8],
+) -> Result<T, ValidationErrors> {
+    let value: T = parse_json(json)?;
+    value.validate()?;
+    Ok(value)
+}
+
+// Deserialization only, for bodies that are checked after conversion.
+pub(crate) fn parse_json<T: DeserializeOwned>(json: &[u8]) -> Result<T, ValidationErrors> {
+    let mut deserializer = serde_json::Deserializer::from_slice(json);
+    let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+        // Broken JSON has no meaningful field to point at.
+        let code = match e.inner().classify() {
+            Category::Syntax | Category::Eof => "malformed_json",
+            _ => "invalid",
+        };
+        ValidationErrors::from_path(e, code)
+    })?;
+    // Anything after the value, e.g. a second object, is malformed too.
+    deserializer
+        .end()
+        .map_err(|e| ValidationErrors::single(".", "malformed_json", e.to_string()))?;
+    Ok(value)
+}
@@ -0,0 +1,268 @@
+use crate::{
+    command::CommandSource,
+    dispatch::Dispatcher,
+    health::{Component, Health},
+    history::Source,
+    i18n::tr,
+    model::Interval,
+    template, MotionTracker, NotificationConfig,
+};
+use regex::Regex;
+use serde::Deserialize;
+use std::{
+    ffi::OsStr,
+    fs,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+    time::{Duration, Instant, UNIX_EPOCH},
+};
+use tracing::{debug, error, info};
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct WatchConfig {
+    pub(crate) file_exists: Option<PathBuf>,
+    pub(crate) file_changed: Option<PathBuf>,
+    pub(crate) process_exited: Option<String>,
+    pub(crate) http_failing: Option<String>,
+    pub(crate) command_matches: Option<CommandMatch>,
+    pub(crate) debounce: Option<Interval>,
+    #[serde(default = "default_once")]
+    pub(crate) once: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct CommandMatch {
+    pub(crate) command: CommandSource,
+    pub(crate) pattern: String,
+}
+
+fn default_once() -> bool {
+    true
+}
+
+enum Condition {
+    FileExists(PathBuf),
+    FileChanged {
+        path: PathBuf,
+        baseline: String,
+    },
+    ProcessExited {
+        process: String,
+        seen_running: bool,
+    },
+    HttpFailing {
+        url: String,
+        client: reqwest::Client,
+        runtime: tokio::runtime::Handle,
+    },
+    CommandMatches {
+        command: CommandSource,
+        pattern: Regex,
+        allowlist: Arc<Vec<PathBuf>>,
+    },
+}
+
+impl WatchConfig {
+    fn condition(
+        &self,
+        allowlist: Arc<Vec<PathBuf>>,
+        runtime: tokio::runtime::Handle,
+    ) -> Result<Condition, String> {
+        if let Some(path) = &self.file_exists {
+            return Ok(Condition::FileExists(path.clone()));
+        }
+        if let Some(path) = &self.file_changed {
+            return Ok(Condition::FileChanged {
+                baseline: file_fingerprint(path),
+                path: path.clone(),
+            });
+        }
+        if let Some(process) = &self.process_exited {
+            return Ok(Condition::ProcessExited {
+                process: process.clone(),
+                seen_running: false,
+            });
+        }
+        if let Some(url) = &self.http_failing {
+            let client = reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .map_err(|e| e.to_string())?;
+            return Ok(Condition::HttpFailing {
+                url: url.clone(),
+                client,
+                runtime,
+            });
+        }
+        if let Some(matcher) = &self.command_matches {
+            let pattern = Regex::new(&matcher.pattern).map_err(|e| e.to_string())?;
+            return Ok(Condition::CommandMatches {
+                command: matcher.command.clone(),
+                pattern,
+                allowlist,
+            });
+        }
+        Err(tr!(WatchInvalidCondition).to_string())
+    }
+}
+
+impl Condition {
+    fn describe(&self) -> String {
+        match self {
+            Condition::FileExists(path) | Condition::FileChanged { path, .. } => {
+                path.display().to_string()
+            }
+            Condition::ProcessExited { process, .. } => process.clone(),
+            Condition::HttpFailing { url, .. } => url.clone(),
+            Condition::CommandMatches { command, .. } => command.program.display().to_string(),
+        }
+    }
+
+    // `Some(state)` while the condition holds. A different state while still
+    // holding (e.g. the file changed again) restarts the debounce timer.
+    fn check(&mut self) -> Result<Option<String>, String> {
+        match self {
+            Condition::FileExists(path) => Ok(path.exists().then(String::new)),
+            Condition::FileChanged { path, baseline } => {
+                let current = file_fingerprint(path);
+                Ok((current != *baseline).then_some(current))
+            }
+            Condition::ProcessExited {
+                process,
+                seen_running,
+            } => {
+                let running = process_running(process);
+                *seen_running |= running;
+                Ok((*seen_running && !running).then(String::new))
+            }
+            Condition::HttpFailing {
+                url,
+                client,
+                runtime,
+            } => {
+                let result = runtime.block_on(client.get(url.as_str()).send());
+                Ok(match result {
+                    Ok(response) if response.status().is_success() => None,
+                    Ok(response) => Some(format!("HTTP {}", response.status())),
+                    Err(e) => Some(e.to_string()),
+                })
+            }
+            Condition::CommandMatches {
+                command,
+                pattern,
+                allowlist,
+            } => Ok(command
+                .run(allowlist)?
+                .filter(|output| pattern.is_match(output))),
+        }
+    }
+
+    fn fired(&mut self, state: &str) {
+        match self {
+            Condition::FileChanged { baseline, .. } => *baseline = state.to_string(),
+            Condition::ProcessExited { seen_running, .. } => *seen_running = false,
+            _ => {}
+        }
+    }
+}
+
+fn file_fingerprint(path: &Path) -> String {
+    match fs::metadata(path) {
+        Ok(meta) => {
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos())
+                .unwrap_or(0);
+            format!("{}:{}", meta.len(), modified)
+        }
+        Err(_) => "missing".to_string(),
+    }
+}
+
+// A numeric name is treated as a PID, anything else is matched against the
+// process names in /proc.
+fn process_running(process: &str) -> bool {
+    if process.chars().all(|c| c.is_ascii_digit()) {
+        return PathBuf::from("/proc").join(process).exists();
+    }
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return false;
+    };
+    entries
+        .flatten()
+        .any(|entry| process_names(&entry.path()).any(|name| name == process))
+}
+
+// `comm` is truncated to 15 bytes, so the basename of `argv[0]` is tried too.
+fn process_names(dir: &Path) -> impl Iterator<Item = String> {
+    let comm = fs::read_to_string(dir.join("comm"))
+        .ok()
+        .map(|comm| comm.trim_end().to_string());
+    let argv0 = fs::read(dir.join("cmdline")).ok().and_then(|cmdline| {
+        let argv0 = cmdline.split(|b| *b == 0).next()?;
+        let name = Path::new(OsStr::from_bytes(argv0)).file_name()?;
+        Some(name.to_string_lossy().into_owned())
+    });
+    comm.into_iter().chain(argv0)
+}
+
+pub(crate) fn spawn(
+    notif: NotificationConfig,
+    watch: WatchConfig,
+    allowlist: Arc<Vec<PathBuf>>,
+    motion_tracker: MotionTracker,
+    dispatcher: Dispatcher,
+    health: Health,
+) -> Result<(), String> {
+    let mut condition = watch.condition(allowlist, motion_tracker.runtime_handle.clone())?;
+    let debounce = watch.debounce.map(Interval::duration).unwrap_or_default();
+    let origin = Source::Watch {
+        name: notif.name().to_string(),
+    };
+
+    thread::spawn(move || {
+        info!("{}", tr!(WatchStarted, target = condition.describe()));
+        let mut holding: Option<(String, Instant)> = None;
+        let mut fired = false;
+
+        loop {
+            match condition.check() {
+                Ok(Some(state)) => {
+                    let since = match holding.take() {
+                        Some((previous, since)) if previous == state => since,
+                        _ => Instant::now(),
+                    };
+                    if since.elapsed() >= debounce && !(watch.once && fired) {
+                        let mut vars = template::builtin_vars(motion_tracker.active_minutes());
+                        vars.insert("target".to_string(), condition.describe());
+                        vars.insert("output".to_string(), state.clone());
+                        dispatcher.submit(notif.render(&vars), origin.clone(), None);
+                        info!("{}", tr!(WatchFired, target = condition.describe()));
+                        condition.fired(&state);
+                        fired = true;
+                    }
+                    holding = Some((state, since));
+                }
+                Ok(None) => {
+                    if fired {
+                        debug!("{}", tr!(WatchCleared, target = condition.describe()));
+                    }
+                    holding = None;
+                    fired = false;
+                }
+                Err(e) => {
+                    let message = tr!(WatchCheckFailed, target = condition.describe(), error = e);
+                    error!("{}", message);
+                    health.error(Component::Scheduler, message);
+                }
+            }
+            thread::sleep(notif.interval.next_delay());
+        }
+    });
+
+    Ok(())
+}